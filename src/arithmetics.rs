@@ -4,6 +4,7 @@ use colored::Colorize;
 use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
 use crate::ParmesanCloudovo;
 use crate::cloudovo::*;
+use crate::cloudovo::rounding::RoundingMode;
 
 
 // =============================================================================
@@ -12,7 +13,7 @@ use crate::cloudovo::*;
 //
 
 /// Parmesan Arithmetics Trait
-pub trait ParmArithmetics {
+pub trait ParmArithmetics: Sized {
     /// Zero: `0`
     fn zero() -> Self;
 
@@ -70,13 +71,41 @@ pub trait ParmArithmetics {
         k: i64,
     ) -> Self;
 
+    /// Sum: `X_1 + X_2 + ... + X_n`
+    fn sum(
+        pc: &ParmesanCloudovo,
+        xs: &[Self],
+    ) -> Self;
+
     /// Scalar multiplication (by an integer): `k·X`
     fn scalar_mul(
         pc: &ParmesanCloudovo,
-        k: i32,
+        k: i64,
         x: &Self,
     ) -> Self;
 
+    /// Scalar multiplication (by an integer) with minimal bootstrap depth: `k·X`
+    fn scalar_mul_latency(
+        pc: &ParmesanCloudovo,
+        k: i64,
+        x: &Self,
+    ) -> Self;
+
+    /// Multi-scalar multiplication (by integers): `k_1·X, k_2·X, ...`
+    fn multi_scalar_mul(
+        pc: &ParmesanCloudovo,
+        ks: &[i64],
+        x: &Self,
+    ) -> Vec<Self>;
+
+    /// Dot product with constant weights: `Σ w_i·X_i + b`
+    fn dot_const(
+        pc: &ParmesanCloudovo,
+        ws: &[i64],
+        xs: &[Self],
+        b: i64,
+    ) -> Self;
+
     /// Signum: `sgn(X)`
     fn sgn(
         pc: &ParmesanCloudovo,
@@ -96,6 +125,12 @@ pub trait ParmArithmetics {
         x: &Self,
     ) -> Self;
 
+    /// Arg-max: index of `max{X_0, ..., X_{n-1}}` (the first one on ties)
+    fn argmax(
+        pc: &ParmesanCloudovo,
+        xs: &[Self],
+    ) -> Self;
+
     /// Multiplication: `X × Y`
     fn mul(
         pc: &ParmesanCloudovo,
@@ -103,12 +138,46 @@ pub trait ParmArithmetics {
         y: &Self,
     ) -> Self;
 
+    /// Inner product: `Σ X_i × Y_i`
+    fn inner_prod(
+        pc: &ParmesanCloudovo,
+        xs: &[Self],
+        ys: &[Self],
+    ) -> Self;
+
+    /// Matrix-vector product: `M × X` (`M` given row-wise)
+    fn mat_vec(
+        pc: &ParmesanCloudovo,
+        m: &[Vec<Self>],
+        x: &[Self],
+    ) -> Vec<Self>;
+
     /// Squaring: `X²`
     fn squ(
         pc: &ParmesanCloudovo,
         x: &Self,
     ) -> Self;
 
+    /// Integer square root: `⌊√X⌋` (zero for `X < 0`)
+    fn isqrt(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+    ) -> Self;
+
+    /// Exponentiation (by a public exponent): `X^e`
+    fn pow(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        e: u32,
+    ) -> Self;
+
+    /// Polynomial evaluation (with plaintext coefficients): `Σ c_i·X^i`
+    fn poly_eval(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        coeffs: &[i64],
+    ) -> Self;
+
     /// Rounding
     fn round_at(
         pc: &ParmesanCloudovo,
@@ -116,7 +185,36 @@ pub trait ParmArithmetics {
         pos: usize,
     ) -> Self;
 
-    //WISH noisy variant of round_at?
+    /// Rounding with given mode
+    fn round_at_mode(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> Self;
+
+    /// Noisy Rounding with given mode
+    /// (n.b., only when result gets immediately decrypted)
+    fn round_at_noisy(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> Self;
+
+    /// Rescaling (rounded division by a power of two): `round(X / 2^pos)`
+    fn rescale(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        pos: usize,
+    ) -> Self;
+
+    /// Trimming to (at most) `bits + 1` words, given that `|X| < 2^bits` (value is kept)
+    fn trim(
+        pc: &ParmesanCloudovo,
+        x: &Self,
+        bits: usize,
+    ) -> Self;
 }
 
 impl ParmArithmetics for i64 {
@@ -160,11 +258,35 @@ impl ParmArithmetics for i64 {
         k: i64,
     ) -> i64 {x + k}
 
+    fn sum(
+        _pc: &ParmesanCloudovo,
+        xs: &[i64],
+    ) -> i64 {xs.iter().sum()}
+
     fn scalar_mul(
         _pc: &ParmesanCloudovo,
-        k: i32,
+        k: i64,
+        x: &i64,
+    ) -> i64 {k * x}
+
+    fn scalar_mul_latency(
+        _pc: &ParmesanCloudovo,
+        k: i64,
+        x: &i64,
+    ) -> i64 {k * x}
+
+    fn multi_scalar_mul(
+        _pc: &ParmesanCloudovo,
+        ks: &[i64],
         x: &i64,
-    ) -> i64 {(k as i64) * x}
+    ) -> Vec<i64> {ks.iter().map(|ki| ki * x).collect()}
+
+    fn dot_const(
+        _pc: &ParmesanCloudovo,
+        ws: &[i64],
+        xs: &[i64],
+        b: i64,
+    ) -> i64 {ws.iter().zip(xs.iter()).map(|(wi, xi)| wi * xi).sum::<i64>() + b}
 
     fn sgn(
         _pc: &ParmesanCloudovo,
@@ -182,17 +304,57 @@ impl ParmArithmetics for i64 {
         x: &i64,
     ) -> i64 {std::cmp::max(0, *x)}
 
+    fn argmax(
+        _pc: &ParmesanCloudovo,
+        xs: &[i64],
+    ) -> i64 {
+        let m = xs.iter().max();
+        xs.iter().position(|xi| Some(xi) == m).expect("ParmArithmetics::argmax failed.") as i64
+    }
+
     fn mul(
         _pc: &ParmesanCloudovo,
         x: &i64,
         y: &i64,
     ) -> i64 {x * y}
 
+    fn inner_prod(
+        _pc: &ParmesanCloudovo,
+        xs: &[i64],
+        ys: &[i64],
+    ) -> i64 {xs.iter().zip(ys.iter()).map(|(xi, yi)| xi * yi).sum()}
+
+    fn mat_vec(
+        pc: &ParmesanCloudovo,
+        m: &[Vec<i64>],
+        x: &[i64],
+    ) -> Vec<i64> {m.iter().map(|mi| ParmArithmetics::inner_prod(pc, mi, x)).collect()}
+
     fn squ(
         _pc: &ParmesanCloudovo,
         x: &i64,
     ) -> i64 {x * x}
 
+    fn isqrt(
+        _pc: &ParmesanCloudovo,
+        x: &i64,
+    ) -> i64 {
+        if *x <= 0 {return 0;}
+        x.isqrt()
+    }
+
+    fn pow(
+        _pc: &ParmesanCloudovo,
+        x: &i64,
+        e: u32,
+    ) -> i64 {x.pow(e)}
+
+    fn poly_eval(
+        _pc: &ParmesanCloudovo,
+        x: &i64,
+        coeffs: &[i64],
+    ) -> i64 {coeffs.iter().rev().fold(0, |acc, c| acc * x + c)}
+
     fn round_at(
         _pc: &ParmesanCloudovo,
         x: &i64,
@@ -209,6 +371,56 @@ impl ParmArithmetics for i64 {
             },
         }
     }
+
+    fn round_at_mode(
+        _pc: &ParmesanCloudovo,
+        x: &i64,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> i64 {
+        match pos {
+            0 => { *x },
+            p if p >= 63 => { panic!("Rounding position ≥ 63 (for i64).") },
+            _ => {
+                // x = q·2^pos + l, where 0 ≤ l < 2^pos
+                let q = x >> pos;
+                let l = x - (q << pos);
+                let half = 1i64 << (pos-1);
+                let r = match mode {
+                    RoundingMode::HalfUp        => (l >= half) as i64,
+                    RoundingMode::HalfAway      => if *x >= 0 {(l >= half) as i64} else {(l > half) as i64},
+                    RoundingMode::HalfEven      => (l > half || (l == half && q & 1 == 1)) as i64,
+                    RoundingMode::Floor         => 0,
+                    RoundingMode::Ceil          => (l > 0) as i64,
+                    RoundingMode::TowardZero    => if *x >= 0 {0} else {(l > 0) as i64},
+                };
+                (q + r) << pos
+            },
+        }
+    }
+
+    fn round_at_noisy(
+        pc: &ParmesanCloudovo,
+        x: &i64,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> i64 {
+        Self::round_at_mode(pc, x, pos, mode)
+    }
+
+    fn rescale(
+        pc: &ParmesanCloudovo,
+        x: &i64,
+        pos: usize,
+    ) -> i64 {
+        Self::round_at(pc, x, pos) >> pos
+    }
+
+    fn trim(
+        _pc: &ParmesanCloudovo,
+        x: &i64,
+        _bits: usize,
+    ) -> i64 {*x}
 }
 
 impl ParmArithmetics for ParmCiphertext {
@@ -339,9 +551,27 @@ impl ParmArithmetics for ParmCiphertext {
         res
     }
 
+    fn sum(
+        pc: &ParmesanCloudovo,
+        xs: &[ParmCiphertext],
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = addition::sum_impl(
+            pc,
+            xs,
+        ).expect("ParmArithmetics::sum failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
     fn scalar_mul(
         pc: &ParmesanCloudovo,
-        k: i32,
+        k: i64,
         x: &ParmCiphertext,
     ) -> ParmCiphertext {
         #[cfg(feature = "seq_analyze")]
@@ -351,6 +581,8 @@ impl ParmArithmetics for ParmCiphertext {
             pc,
             k,
             x,
+            scalar_multiplication::ScmObjective::Throughput,
+            None,
         ).expect("ParmArithmetics::scalar_mul failed.");
 
         #[cfg(feature = "seq_analyze")]
@@ -359,6 +591,74 @@ impl ParmArithmetics for ParmCiphertext {
         res
     }
 
+    fn scalar_mul_latency(
+        pc: &ParmesanCloudovo,
+        k: i64,
+        x: &ParmCiphertext,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = scalar_multiplication::scalar_mul_impl(
+            pc,
+            k,
+            x,
+            scalar_multiplication::ScmObjective::Latency,
+            None,
+        ).expect("ParmArithmetics::scalar_mul_latency failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn multi_scalar_mul(
+        pc: &ParmesanCloudovo,
+        ks: &[i64],
+        x: &ParmCiphertext,
+    ) -> Vec<ParmCiphertext> {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = scalar_multiplication::multi_scalar_mul_impl(
+            pc,
+            ks,
+            x,
+            scalar_multiplication::ScmObjective::Throughput,
+            None,
+        ).expect("ParmArithmetics::multi_scalar_mul failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn dot_const(
+        pc: &ParmesanCloudovo,
+        ws: &[i64],
+        xs: &[ParmCiphertext],
+        b: i64,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = scalar_multiplication::dot_const_impl(
+            pc,
+            ws,
+            xs,
+            b,
+            scalar_multiplication::ScmObjective::Throughput,
+            None,
+        ).expect("ParmArithmetics::dot_const failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
     fn sgn(
         pc: &ParmesanCloudovo,
         x: &ParmCiphertext,
@@ -416,6 +716,24 @@ impl ParmArithmetics for ParmCiphertext {
         res
     }
 
+    fn argmax(
+        pc: &ParmesanCloudovo,
+        xs: &[ParmCiphertext],
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = maximum::argmax_impl(
+            pc,
+            xs,
+        ).expect("ParmArithmetics::argmax failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
     fn mul(
         pc: &ParmesanCloudovo,
         x: &ParmCiphertext,
@@ -436,6 +754,46 @@ impl ParmArithmetics for ParmCiphertext {
         res
     }
 
+    fn inner_prod(
+        pc: &ParmesanCloudovo,
+        xs: &[ParmCiphertext],
+        ys: &[ParmCiphertext],
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = multiplication::inner_prod_impl(
+            pc,
+            xs,
+            ys,
+        ).expect("ParmArithmetics::inner_prod failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn mat_vec(
+        pc: &ParmesanCloudovo,
+        m: &[Vec<ParmCiphertext>],
+        x: &[ParmCiphertext],
+    ) -> Vec<ParmCiphertext> {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = multiplication::mat_vec_impl(
+            pc,
+            m,
+            x,
+        ).expect("ParmArithmetics::mat_vec failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
     fn squ(
         pc: &ParmesanCloudovo,
         x: &ParmCiphertext,
@@ -454,6 +812,64 @@ impl ParmArithmetics for ParmCiphertext {
         res
     }
 
+    fn isqrt(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = square_root::isqrt_impl(
+            pc,
+            x,
+        ).expect("ParmArithmetics::isqrt failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn pow(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        e: u32,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = polynomial::pow_impl(
+            pc,
+            x,
+            e,
+        ).expect("ParmArithmetics::pow failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn poly_eval(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        coeffs: &[i64],
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = polynomial::poly_eval_impl(
+            pc,
+            x,
+            coeffs,
+        ).expect("ParmArithmetics::poly_eval failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
     fn round_at(
         pc: &ParmesanCloudovo,
         x: &ParmCiphertext,
@@ -473,4 +889,90 @@ impl ParmArithmetics for ParmCiphertext {
 
         res
     }
+
+    fn round_at_mode(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = rounding::round_at_mode_impl(
+            pc,
+            x,
+            pos,
+            mode,
+            false,
+        ).expect("ParmArithmetics::round_at_mode failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn round_at_noisy(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        pos: usize,
+        mode: RoundingMode,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = rounding::round_at_mode_impl(
+            pc,
+            x,
+            pos,
+            mode,
+            true,
+        ).expect("ParmArithmetics::round_at_noisy failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn rescale(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        pos: usize,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = rounding::rescale_impl(
+            pc,
+            x,
+            pos,
+        ).expect("ParmArithmetics::rescale failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
+
+    fn trim(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        bits: usize,
+    ) -> ParmCiphertext {
+        #[cfg(feature = "seq_analyze")]
+        start_pbs_analysis!();
+
+        let res = signum::trim_impl(
+            pc,
+            x,
+            bits,
+        ).expect("ParmArithmetics::trim failed.");
+
+        #[cfg(feature = "seq_analyze")]
+        finish_pbs_analysis!();
+
+        res
+    }
 }
@@ -204,3 +204,46 @@ pub fn add_const_impl(
 
     Ok(ParmArithmetics::add(pc, x, &ck))
 }
+
+/// Implementation of multi-operand addition (sum of many ciphertexts)
+/// * operands are added pairwise in parallel rounds (a balanced tree of additions)
+/// * in each round, the shortest operands are paired first, an odd longest one is left for the next round
+///   (this way, the result grows as little as possible)
+/// * output length is given by the operands: each round adds at most one word
+pub fn sum_impl(
+    pc: &ParmesanCloudovo,
+    xs: &[ParmCiphertext],
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    let mut rows = xs.to_vec();
+
+    measure_duration!(
+        ["Parallel summation ({} operands)", xs.len()],
+        [
+            while rows.len() > 1 {
+                // sort by length without leading trivial zeros (stable, i.e., keeps the order of xs for equal lengths)
+                rows.sort_by_key(|r| r.len() - r.iter().rev().take_while(|ri| ri.is_triv_zero()).count());
+
+                let mut reduced = vec![ParmCiphertext::empty(); rows.len().div_ceil(2)];
+                // odd number of operands: the longest one goes directly to the next round
+                if rows.len() % 2 == 1 {
+                    *reduced.last_mut().unwrap() = rows.pop().unwrap();
+                }
+
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let red_rows_iter = reduced.par_iter_mut().zip(rows.par_chunks(2));
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let red_rows_iter = reduced.iter_mut().zip(rows.chunks(2));
+
+                red_rows_iter.for_each(| (ri, rows_pair) | {
+                    *ri = ParmArithmetics::add(pc, &rows_pair[0], &rows_pair[1]);
+                });
+
+                rows = reduced;
+            }
+        ]
+    );
+
+    Ok(rows.pop().unwrap_or_else(ParmArithmetics::zero))
+}
@@ -0,0 +1,228 @@
+//!
+//! # Module for Decision Tree & Forest evaluation over a generic type `<T>`
+//!
+//! Thresholds and leaf values are plaintext, features are encrypted. The tree is evaluated obliviously, i.e.,
+//! all comparisons and all paths, then leaf values are aggregated by encrypted leaf indicators:
+//!
+//! ```text
+//!     comparisons:    s_k = sgn(2·(X_f - t) + 1)                  .. in {-1, 1}, i.e., -1 for X_f < t (left), 1 otherwise (right)
+//!     path costs:     C_l = Σ (1 - s_k) over right turns to leaf l
+//!                         + Σ (1 + s_k) over left turns           .. 0 iff leaf l is reached, otherwise ≥ 2
+//!     indicators:     I_l = 1 - sgn(C_l)                          .. in {0, 1}
+//!     output:         Σ v_l·I_l (+ base)
+//! ```
+//!
+//! A forest shares the comparisons of all its trees (each distinct pair `(feature, threshold)` is evaluated once)
+//! and sums the leaf values of all trees in a single dot product.
+
+use std::error::Error;
+use std::collections::BTreeSet;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::*;
+use crate::arithmetics::ParmArithmetics;
+
+/// Node of decision tree: inputs with `x[feature] < threshold` go left, others go right, e.g.:
+///
+/// ```yaml
+/// node: split
+/// feature: 0
+/// threshold: 5
+/// left: {node: leaf, value: -3}
+/// right: {node: leaf, value: 7}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "node", rename_all = "snake_case")]
+pub enum TreeNode {
+    Split {
+        feature: usize,
+        threshold: i64,
+        left: Box<TreeNode>,
+        right: Box<TreeNode>,
+    },
+    Leaf {
+        value: i64,
+    },
+}
+
+impl TreeNode {
+
+    /// Evaluate a single decision tree (all its features must be within inputs)
+    pub fn eval<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        inputs: &[T],
+    ) -> Result<T, Box<dyn Error>> {
+        self.validate(inputs.len())?;
+        Ok(eval_trees(pc, std::slice::from_ref(self), 0, inputs))
+    }
+
+    /// Check the structure of decision tree: features are within inputs, thresholds are not too large
+    pub fn validate(&self, n_features: usize) -> Result<(), Box<dyn Error>> {
+        match self {
+            TreeNode::Split {feature, threshold, left, right} => {
+                if *feature >= n_features {
+                    return Err(format!("Split on feature {} out of {} features.", feature, n_features).into());
+                }
+                // n.b., 2·threshold must fit
+                if threshold.unsigned_abs() >= 1 << 61 {
+                    return Err(format!("Threshold {} out of range.", threshold).into());
+                }
+                left.validate(n_features)?;
+                right.validate(n_features)
+            },
+            TreeNode::Leaf {..} => Ok(()),
+        }
+    }
+
+    /// Depth of decision tree (a single leaf has depth 0)
+    pub fn depth(&self) -> usize {
+        match self {
+            TreeNode::Split {left, right, ..} => 1 + left.depth().max(right.depth()),
+            TreeNode::Leaf {..} => 0,
+        }
+    }
+
+    /// Collect distinct splits
+    fn splits(&self, acc: &mut BTreeSet<(usize, i64)>) {
+        if let TreeNode::Split {feature, threshold, left, right} = self {
+            acc.insert((*feature, *threshold));
+            left.splits(acc);
+            right.splits(acc);
+        }
+    }
+
+    /// Collect leaves with their paths: indexes into `splits` and directions (`true` for right)
+    fn leaves(
+        &self,
+        splits: &[(usize, i64)],
+        path: &mut Vec<(usize, bool)>,
+        acc: &mut Vec<(Vec<(usize, bool)>, i64)>,
+    ) {
+        match self {
+            TreeNode::Split {feature, threshold, left, right} => {
+                let k = splits.binary_search(&(*feature, *threshold)).expect("binary_search failed.");
+                path.push((k, false));
+                left.leaves(splits, path, acc);
+                path.pop();
+                path.push((k, true));
+                right.leaves(splits, path, acc);
+                path.pop();
+            },
+            TreeNode::Leaf {value} => acc.push((path.clone(), *value)),
+        }
+    }
+}
+
+/// Forest of decision trees: the output is the sum of leaf values of all trees (plus a base), e.g.,
+/// a random forest with summed votes, or gradient-boosted trees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Forest {
+    pub trees: Vec<TreeNode>,
+    // it is convenient to hold the input size
+    pub n_features: usize,
+    // added to the sum of leaf values
+    #[serde(default)]
+    pub base: i64,
+}
+
+impl Forest {
+
+    /// Load Forest from a JSON string
+    pub fn from_json_str(json_str: &str) -> Result<Forest, Box<dyn Error>> {
+        let forest: Forest = serde_json::from_str(json_str)?;
+        forest.validate()?;
+        Ok(forest)
+    }
+
+    /// Load Forest from a YAML string
+    pub fn from_yaml_str(yaml_str: &str) -> Result<Forest, Box<dyn Error>> {
+        let forest: Forest = serde_yaml::from_str(yaml_str)?;
+        forest.validate()?;
+        Ok(forest)
+    }
+
+    /// Check the structure of Forest: there is at least one feature and one tree, each tree is valid
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.n_features == 0 {return Err("Forest has no features.".into());}
+        if self.trees.is_empty() {return Err("Forest has no trees.".into());}
+        for (ti, tree) in self.trees.iter().enumerate() {
+            tree.validate(self.n_features).map_err(|e| format!("Tree {} of Forest: {}", ti, e))?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate Forest
+    pub fn eval<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        inputs: &[T],
+    ) -> Result<T, Box<dyn Error>> {
+        self.validate()?;
+        if inputs.len() != self.n_features {
+            return Err(format!("Forest expects {} features, got {}.", self.n_features, inputs.len()).into());
+        }
+        Ok(eval_trees(pc, &self.trees, self.base, inputs))
+    }
+}
+
+/// Evaluate trees: shared comparisons, leaf indicators of all trees, then a single dot product with leaf values
+/// * n.b., trees must be validated against inputs
+fn eval_trees<T: Clone + ParmArithmetics + Send + Sync>(
+    pc: &ParmesanCloudovo,
+    trees: &[TreeNode],
+    base: i64,
+    inputs: &[T],
+) -> T {
+    let mut split_set = BTreeSet::new();
+    trees.iter().for_each(|tree| tree.splits(&mut split_set));
+    let splits: Vec<(usize, i64)> = split_set.into_iter().collect();
+
+    let mut leaves = Vec::new();
+    trees.iter().for_each(|tree| tree.leaves(&splits, &mut Vec::new(), &mut leaves));
+
+    measure_duration!(
+        ["Decision trees evaluation over {} ({} trees, {} comparisons, {} leaves)", std::any::type_name::<T>(), trees.len(), splits.len(), leaves.len()],
+        [
+            // comparisons: s_k = sgn(2·(X_f - t) + 1)
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let split_iter = splits.par_iter();
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let split_iter = splits.iter();
+
+            let cmps: Vec<T> = split_iter.map(|(f, t)| {
+                let x_2 = ParmArithmetics::shift(pc, &inputs[*f], 1);
+                ParmArithmetics::sgn(pc, &ParmArithmetics::add_const(pc, &x_2, 1 - 2 * t))
+            }).collect();
+
+            // leaf indicators: I_l = 1 - sgn(C_l)
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let leaf_iter = leaves.par_iter();
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let leaf_iter = leaves.iter();
+
+            let inds: Vec<T> = leaf_iter.map(|(path, _)| {
+                let turns: Vec<T> = path.iter().map(|(k, right)| if *right {ParmArithmetics::opp(&cmps[*k])} else {cmps[*k].clone()}).collect();
+                let cost = ParmArithmetics::add_const(pc, &ParmArithmetics::sum(pc, &turns), path.len() as i64);
+                ParmArithmetics::add_const(pc, &ParmArithmetics::opp(&ParmArithmetics::sgn(pc, &cost)), 1)
+            }).collect();
+
+            // output: Σ v_l·I_l + base
+            let values: Vec<i64> = leaves.iter().map(|(_, v)| *v).collect();
+            let out = ParmArithmetics::dot_const(pc, &values, &inds, base);
+        ]
+    );
+
+    out
+}
@@ -0,0 +1,204 @@
+use std::error::Error;
+
+use crate::*;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
+use crate::userovo::encryption::PARM_CT_MAXLEN;
+
+
+// =============================================================================
+//
+//  Fixed-Point Numbers
+//
+
+/// Encrypted fixed-point number: `X / 2^frac`
+/// * the binary-point position `frac` (i.e., the number of fractional words) is public
+/// * operands of different positions are aligned by `shift`, products are rescaled by `round_at`
+#[derive(Clone)]
+pub struct ParmFixed {
+    pub ct: ParmCiphertext,
+    pub frac: usize,
+}
+
+impl ParmFixed {
+    /// Fixed-point number from a ciphertext & binary-point position
+    pub fn new(
+        ct: ParmCiphertext,
+        frac: usize,
+    ) -> ParmFixed {
+        ParmFixed {ct, frac}
+    }
+
+    /// Zero (with no fractional words)
+    pub fn zero() -> ParmFixed {
+        ParmFixed::new(ParmCiphertext::empty(), 0)
+    }
+
+    /// Trivial constant: `m` quantized to `frac` fractional bits
+    pub fn constant(
+        pc: &ParmesanCloudovo,
+        m: f64,
+        frac: usize,
+    ) -> Result<ParmFixed, Box<dyn Error>> {
+        Ok(ParmFixed::new(ParmArithmetics::add_const(pc, &ParmCiphertext::empty(), Self::quantize(m, frac)?), frac))
+    }
+
+    /// Quantize `m` to `frac` fractional bits: `round(m·2^frac)`
+    pub fn quantize(
+        m: f64,
+        frac: usize,
+    ) -> Result<i64, Box<dyn Error>> {
+        let mi = (m * 2f64.powi(frac as i32)).round();
+        if !mi.is_finite() || mi.abs() >= (1u64 << (PARM_CT_MAXLEN - 1)) as f64 {
+            return Err(format!("Value {} with {} fractional bits out of range.", m, frac).into());
+        }
+        Ok(mi as i64)
+    }
+
+    /// Value of an integer `mi` with `frac` fractional bits: `mi / 2^frac`
+    pub fn dequantize(
+        mi: i64,
+        frac: usize,
+    ) -> f64 {
+        mi as f64 / 2f64.powi(frac as i32)
+    }
+
+    /// Opposite: `-X`
+    pub fn opp(x: &ParmFixed) -> ParmFixed {
+        ParmFixed::new(ParmArithmetics::opp(&x.ct), x.frac)
+    }
+
+    /// Change binary-point position to `frac`
+    /// * more fractional words: shift
+    /// * less fractional words: round at the new position & drop (zero) lower words (cf. `ParmArithmetics::rescale`)
+    pub fn rescale(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        frac: usize,
+    ) -> ParmFixed {
+        if frac >= x.frac {
+            return ParmFixed::new(ParmArithmetics::shift(pc, &x.ct, frac - x.frac), frac);
+        }
+
+        ParmFixed::new(ParmArithmetics::rescale(pc, &x.ct, x.frac - frac), frac)
+    }
+
+    /// Addition: `X + Y` (aligned to the finer position)
+    pub fn add(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        y: &ParmFixed,
+    ) -> ParmFixed {
+        let (ax, ay) = Self::align(pc, x, y);
+        ParmFixed::new(ParmArithmetics::add(pc, &ax.ct, &ay.ct), ax.frac)
+    }
+
+    /// Subtraction: `X - Y` (aligned to the finer position)
+    pub fn sub(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        y: &ParmFixed,
+    ) -> ParmFixed {
+        let (ax, ay) = Self::align(pc, x, y);
+        ParmFixed::new(ParmArithmetics::sub(pc, &ax.ct, &ay.ct), ax.frac)
+    }
+
+    /// Multiplication: `X · Y`, rescaled to `frac` fractional words
+    pub fn mul(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        y: &ParmFixed,
+        frac: usize,
+    ) -> ParmFixed {
+        let xy = ParmFixed::new(ParmArithmetics::mul(pc, &x.ct, &y.ct), x.frac + y.frac);
+        Self::rescale(pc, &xy, frac)
+    }
+
+    /// Scalar multiplication by a fractional constant: `k · X`, where `k` is quantized to `k_frac` fractional bits
+    /// * the result keeps the position of `X`
+    pub fn scalar_mul(
+        pc: &ParmesanCloudovo,
+        k: f64,
+        k_frac: usize,
+        x: &ParmFixed,
+    ) -> Result<ParmFixed, Box<dyn Error>> {
+        let ki = Self::quantize(k, k_frac)?;
+        let kx = ParmFixed::new(ParmArithmetics::scalar_mul(pc, ki, &x.ct), x.frac + k_frac);
+        Ok(Self::rescale(pc, &kx, x.frac))
+    }
+
+    /// Polynomial evaluation with fractional coefficients: `Σ c_i·X^i`
+    /// * Estrin's scheme (cf. `polynomial::poly_eval_impl`), coefficients are quantized to the position of `X`
+    /// * every product is rounded back to the position of `X`
+    pub fn poly_eval(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        coeffs: &[f64],
+    ) -> Result<ParmFixed, Box<dyn Error>> {
+        // trim leading zero coefficients
+        let deg_p1 = coeffs.iter().rposition(|c| *c != 0.0).map_or(0, |d| d + 1);
+        if deg_p1 == 0 {return Ok(ParmFixed::new(ParmCiphertext::empty(), x.frac));}
+        let cis = coeffs[0..deg_p1].iter().map(|c| Self::quantize(*c, x.frac)).collect::<Result<Vec<i64>, _>>()?;
+        polynomial::check_squ_len(x.ct.len(), deg_p1 - 1, x.frac)?;
+
+        // level 0: c_2i + c_(2i+1)·X
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let c_iter = cis.par_chunks(2);
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let c_iter = cis.chunks(2);
+
+        let mut terms: Vec<ParmFixed> = c_iter.map(|cc| {
+            let c0 = ParmFixed::new(ParmArithmetics::add_const(pc, &ParmCiphertext::empty(), cc[0]), x.frac);
+            if cc.len() == 2 {
+                let c1_x = ParmFixed::new(ParmArithmetics::scalar_mul(pc, cc[1], &x.ct), 2 * x.frac);
+                Self::add(pc, &c0, &Self::rescale(pc, &c1_x, x.frac))
+            } else {
+                c0
+            }
+        }).collect();
+
+        // next levels: p_2i + p_(2i+1)·X^(2^j)
+        let mut xp = x.clone();
+        while terms.len() > 1 {
+            xp = Self::mul(pc, &xp, &xp, x.frac);
+
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let t_iter = terms.par_chunks(2);
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let t_iter = terms.chunks(2);
+
+            // n.b., products via mul-array of `inner_prod`, cf. `polynomial::poly_eval_impl`
+            terms = t_iter.map(|tc| {
+                if tc.len() == 2 {
+                    let t1_xp = ParmFixed::new(ParmArithmetics::inner_prod(pc, std::slice::from_ref(&tc[1].ct), std::slice::from_ref(&xp.ct)), 2 * x.frac);
+                    Self::add(pc, &tc[0], &Self::rescale(pc, &t1_xp, x.frac))
+                } else {
+                    tc[0].clone()
+                }
+            }).collect();
+        }
+
+        Ok(terms.pop().unwrap_or_else(Self::zero))
+    }
+
+    /// Align two numbers to the finer binary-point position
+    fn align(
+        pc: &ParmesanCloudovo,
+        x: &ParmFixed,
+        y: &ParmFixed,
+    ) -> (ParmFixed, ParmFixed) {
+        let frac = std::cmp::max(x.frac, y.frac);
+        (Self::rescale(pc, x, frac), Self::rescale(pc, y, frac))
+    }
+}
@@ -0,0 +1,292 @@
+use std::error::Error;
+
+use crate::*;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use crossbeam_utils::thread;
+// fake threads for sequential analysis
+#[cfg(feature = "seq_analyze")]
+use crate::seq_utils::thread;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl, ParmEncrWord};
+use crate::userovo::encryption::PARM_CT_MAXLEN;
+use super::{pbs, signum, maximum};
+
+
+// =============================================================================
+//
+//  Normalization
+//
+
+/// Implementation of oblivious normalization: `X = M · 2^E`, where `2^(n-1) ≤ |M| < 2^n` (or `M = 0`)
+/// * `n` is the length of `X`, `M` has one extra word (n.b., leading words of `X` may be non-zero even if `|X|` is small)
+/// * the shift is found by a binary search: for `s = 2^j, ..., 2, 1`, shift `M` by `s` iff `|M| < 2^(n-s)`
+/// * exponent `E ≤ 0` is the opposite of the encrypted shift, whose bits are the conditions of individual steps
+pub fn normalize_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+) -> Result<(ParmCiphertext, ParmCiphertext), Box<dyn Error>> {
+    let n = x.len();
+    if n == 0 {
+        return Ok((ParmCiphertext::empty(), ParmCiphertext::empty()));
+    } else if n >= PARM_CT_MAXLEN {
+        return Err(format!("Normalization of {}-word ciphertext not supported (max {} words).", n, PARM_CT_MAXLEN - 1).into());
+    }
+
+    // |m| < 2^n holds for any X of length n
+    let mut m = x.clone();
+    m.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));
+
+    // steps: the largest power of two below n, ..., 2, 1
+    let mut n_steps = 0usize;
+    while (1 << n_steps) < n {n_steps += 1;}
+    let mut t = ParmCiphertext::triv(n_steps, pc);
+
+    measure_duration!(
+        ["Normalization ({}-bit, {} steps)", n, n_steps],
+        [
+            for j in (0..n_steps).rev() {
+                let s = 1usize << j;
+                let bnd = (1i64 << (n - s)) - 1;
+
+                // |m| < 2^(n-s)  <=>  bnd + m ≥ 0  &  bnd - m ≥ 0
+                let mut a = ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0);
+                let mut b = ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0);
+                // shifted candidate: the low n words, and the top words (of value in {-1,0,1} if |m| < 2^(n-s)) merged by signum
+                let mut m_sh = ParmCiphertext::empty();
+
+                let ar = &mut a;
+                let br = &mut b;
+                let m_shr = &mut m_sh;
+                let mr = &m;
+
+                // parallel pool: A, B, shifted M (n.b., for seq_analyze, there are fake implementations in seq_utils)
+                thread::scope(|ab_scope| {
+                    ab_scope.spawn(|_| {
+                        *ar = signum::nonneg_impl(pc, &ParmArithmetics::add_const(pc, mr, bnd)).expect("signum::nonneg_impl failed.");
+                    });
+                    ab_scope.spawn(|_| {
+                        *br = signum::nonneg_impl(pc, &ParmArithmetics::add_const(pc, &ParmArithmetics::opp(mr), bnd)).expect("signum::nonneg_impl failed.");
+                    });
+                    ab_scope.spawn(|_| {
+                        let y = ParmArithmetics::shift(pc, mr, s);
+                        let mut y_low = y[0..n].to_vec();
+                        let y_top = ParmArithmetics::sgn(pc, &y[n..].to_vec());
+                        y_low.push(if y_top.is_empty() {ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0)} else {y_top[0].clone()});
+                        *m_shr = y_low;
+                    });
+                }).expect("thread::scope ab_scope failed.");
+
+                // c = a & b .. a + b in {1, 2}
+                a.add_inplace(&b);
+                let c = pbs::g_2__pi_5__with_val(pc, &a, 1);
+
+                m = maximum::select_impl(pc, &c, &m_sh, &m)?;
+                t[j] = c;
+            }
+        ]
+    );
+
+    Ok((m, ParmArithmetics::opp(&t)))
+}
+
+
+// =============================================================================
+//
+//  Floating-Point Numbers
+//
+
+/// Extra mantissa words when aligning operands of addition
+pub const FLOAT_GUARD: usize = 2;
+
+/// Encrypted floating-point number: `M · 2^E`
+/// * both mantissa `M` and exponent `E` are encrypted, precision `prec` is public
+/// * mantissa is normalized, i.e., `2^(prec-1) ≤ |M| ≤ 2^prec` (or `M = 0`)
+#[derive(Clone)]
+pub struct ParmFloat {
+    pub m: ParmCiphertext,
+    pub e: ParmCiphertext,
+    pub prec: usize,
+}
+
+impl ParmFloat {
+    /// Mantissa & exponent of `x` with given precision: `x ≈ m · 2^e`, where `2^(prec-1) ≤ |m| < 2^prec`
+    pub fn quantize(
+        x: f64,
+        prec: usize,
+    ) -> Result<(i64, i64), Box<dyn Error>> {
+        if !x.is_finite() || prec == 0 || prec >= PARM_CT_MAXLEN - 1 {
+            return Err(format!("Value {} with precision {} out of range.", x, prec).into());
+        }
+        if x == 0.0 {return Ok((0, 0));}
+
+        // |x| / 2^e0 in [1/2, 1)
+        let e0 = x.abs().log2().floor() as i64 + 1;
+        let mut m = (x * 2f64.powi((prec as i64 - e0) as i32)).round() as i64;
+        let mut e = e0 - prec as i64;
+        // rounding may reach 2^prec
+        if m.unsigned_abs() == 1 << prec {m /= 2; e += 1;}
+
+        Ok((m, e))
+    }
+
+    /// Value of mantissa `m` & exponent `e`: `m · 2^e`
+    pub fn dequantize(
+        m: i64,
+        e: i64,
+    ) -> f64 {
+        m as f64 * 2f64.powi(e as i32)
+    }
+
+    /// Normalize `X · 2^E` (of any length) to given precision
+    /// * normalize, then round the mantissa (or shift if it is shorter than `prec`)
+    pub fn from_wide(
+        pc: &ParmesanCloudovo,
+        x: &ParmCiphertext,
+        e: &ParmCiphertext,
+        prec: usize,
+    ) -> Result<ParmFloat, Box<dyn Error>> {
+        let (m, e_m) = normalize_impl(pc, x)?;
+        let e_sum = ParmArithmetics::add(pc, e, &e_m);
+
+        // n.b., 2^(n-1) ≤ |m| < 2^n, where n = x.len()
+        let n = x.len();
+        let (m, k) = if n > prec {
+            let k = n - prec;
+            (ParmArithmetics::rescale(pc, &m, k), k as i64)
+        } else {
+            (ParmArithmetics::shift(pc, &m, prec - n), -((prec - n) as i64))
+        };
+
+        Ok(ParmFloat {
+            m,
+            e: ParmArithmetics::add_const(pc, &e_sum, k),
+            prec,
+        })
+    }
+
+    /// Opposite: `-X`
+    pub fn opp(x: &ParmFloat) -> ParmFloat {
+        ParmFloat {
+            m: ParmArithmetics::opp(&x.m),
+            e: x.e.clone(),
+            prec: x.prec,
+        }
+    }
+
+    /// Addition: `X + Y`
+    /// * operands are swapped so that `X` has the greater exponent, then `Y` is shifted right by the difference
+    ///   (with `FLOAT_GUARD` extra words)
+    pub fn add(
+        pc: &ParmesanCloudovo,
+        x: &ParmFloat,
+        y: &ParmFloat,
+    ) -> Result<ParmFloat, Box<dyn Error>> {
+        let prec = std::cmp::max(x.prec, y.prec);
+
+        // s = (e_x ≥ e_y)
+        let ex_ey = ParmArithmetics::sub(pc, &x.e, &y.e);
+        let s = signum::nonneg_impl(pc, &ex_ey)?;
+
+        // exponents & mantissas of the greater & smaller operand, difference of exponents
+        let e_gr = maximum::select_impl(pc, &s, &x.e, &y.e)?;
+        let m_gr = maximum::select_impl(pc, &s, &x.m, &y.m)?;
+        let m_sm = maximum::select_impl(pc, &s, &y.m, &x.m)?;
+        let d = maximum::select_impl(pc, &s, &ex_ey, &ParmArithmetics::opp(&ex_ey))?;
+
+        // align with guard words
+        let m_gr = ParmArithmetics::shift(pc, &m_gr, FLOAT_GUARD);
+        let m_sm = shift_right_impl(pc, &ParmArithmetics::shift(pc, &m_sm, FLOAT_GUARD), &d)?;
+
+        let e = ParmArithmetics::add_const(pc, &e_gr, -(FLOAT_GUARD as i64));
+        Self::from_wide(pc, &ParmArithmetics::add(pc, &m_gr, &m_sm), &e, prec)
+    }
+
+    /// Subtraction: `X - Y`
+    pub fn sub(
+        pc: &ParmesanCloudovo,
+        x: &ParmFloat,
+        y: &ParmFloat,
+    ) -> Result<ParmFloat, Box<dyn Error>> {
+        Self::add(pc, x, &Self::opp(y))
+    }
+
+    /// Multiplication: `X · Y`
+    pub fn mul(
+        pc: &ParmesanCloudovo,
+        x: &ParmFloat,
+        y: &ParmFloat,
+    ) -> Result<ParmFloat, Box<dyn Error>> {
+        let prec = std::cmp::max(x.prec, y.prec);
+
+        let mut mxy = ParmCiphertext::empty();
+        let mut exy = ParmCiphertext::empty();
+
+        let mxyr = &mut mxy;
+        let exyr = &mut exy;
+
+        // parallel pool: mantissas & exponents (n.b., for seq_analyze, there are fake implementations in seq_utils)
+        thread::scope(|me_scope| {
+            me_scope.spawn(|_| {
+                *mxyr = ParmArithmetics::mul(pc, &x.m, &y.m);
+            });
+            me_scope.spawn(|_| {
+                *exyr = ParmArithmetics::add(pc, &x.e, &y.e);
+            });
+        }).expect("thread::scope me_scope failed.");
+
+        Self::from_wide(pc, &mxy, &exy, prec)
+    }
+
+    /// Comparison: `sgn(X - Y)`
+    pub fn cmp(
+        pc: &ParmesanCloudovo,
+        x: &ParmFloat,
+        y: &ParmFloat,
+    ) -> Result<ParmCiphertext, Box<dyn Error>> {
+        Ok(Self::sgn(pc, &Self::sub(pc, x, y)?))
+    }
+
+    /// Signum: `sgn(X)`
+    pub fn sgn(
+        pc: &ParmesanCloudovo,
+        x: &ParmFloat,
+    ) -> ParmCiphertext {
+        ParmArithmetics::sgn(pc, &x.m)
+    }
+}
+
+/// Implementation of oblivious right shift (truncating): `X >> D`, where `D ≥ 0` is encrypted
+/// * bits of `D` are extracted from the top: `c_j = (D ≥ 2^j)`, then `D -= c_j·2^j` and `X = c_j ? X >> 2^j : X`
+pub fn shift_right_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    d:  &ParmCiphertext,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    if d.len() >= PARM_CT_MAXLEN {
+        return Err(format!("Shift by {}-word ciphertext not supported (max {} words).", d.len(), PARM_CT_MAXLEN - 1).into());
+    }
+
+    let mut xs = x.clone();
+    let mut ds = d.clone();
+
+    measure_duration!(
+        ["Oblivious right shift ({}-bit by {}-bit)", x.len(), d.len()],
+        [
+            // n.b., D < 2^(D.len())
+            for j in (0..d.len()).rev() {
+                let c = signum::nonneg_impl(pc, &ParmArithmetics::add_const(pc, &ds, -(1i64 << j)))?;
+
+                let xs_sh = if xs.len() > (1 << j) {xs[(1 << j)..].to_vec()} else {ParmCiphertext::empty()};
+                xs = maximum::select_impl(pc, &c, &xs_sh, &xs)?;
+                ds = ParmArithmetics::sub(pc, &ds, &ParmArithmetics::shift(pc, &ParmCiphertext::single(c), j));
+            }
+        ]
+    );
+
+    Ok(xs)
+}
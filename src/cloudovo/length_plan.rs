@@ -0,0 +1,398 @@
+//!
+//! # Module for planning of ciphertext lengths in Neural Network evaluation
+//!
+//! From the declared range of inputs, worst-case bounds of all values are propagated through the layers
+//! (interval arithmetic; all activation functions & pooling operations are monotone), which gives
+//! the exact worst-case bit length of each value. After each layer, values are trimmed to `bits + 1` words
+//! (cf. `ParmArithmetics::trim`).
+//!
+//! If a word budget is given, rounding (a `Rescale` layer) is inserted before each layer that would exceed it:
+//!
+//! ```text
+//!     ... -->  Rescale (pos = p)  -->  Layer (b / 2^p)  -->  ...      .. all subsequent values are scaled by 2^-p
+//! ```
+//!
+//! n.b., intermediate ciphertexts within a layer (e.g., partial sums) may be longer by a few carry words.
+
+use std::error::Error;
+use std::fmt;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+
+use crate::*;
+use crate::arithmetics::ParmArithmetics;
+use super::neural_network::{Perceptron, PercType, Activation, PoolOp, Layer, NeuralNetwork};
+
+/// Bounds of a value: `[lo, hi]`
+pub(crate) type Bounds = (i128, i128);
+
+/// Values beyond are considered an overflow (n.b., rounding requires a spare bit of `i64`)
+const BOUND_MAX_BITS: usize = 62;
+
+/// Plan of a single layer
+#[derive(Debug, Clone)]
+pub struct LayerPlan {
+    // layer inserted by the planner
+    pub inserted: bool,
+    // bit length of accumulated values (before activation), i.e., `|acc| < 2^acc_bits`
+    pub acc_bits: usize,
+    // worst-case bounds & bit lengths of outputs
+    pub out_bounds: Vec<(i64, i64)>,
+    pub out_bits: Vec<usize>,
+}
+
+impl LayerPlan {
+    /// The largest number of words of a value (after trimming)
+    pub fn words(&self) -> usize {
+        self.acc_bits.max(self.out_bits.iter().copied().max().unwrap_or(0)) + 1
+    }
+}
+
+/// Plan of ciphertext lengths of Neural Network
+#[derive(Debug, Clone)]
+pub struct LengthPlan {
+    // planned network (incl. inserted rounding)
+    pub nn: NeuralNetwork,
+    // declared range of inputs & their bit length
+    pub in_range: (i64, i64),
+    pub in_bits: usize,
+    // word budget (if any)
+    pub max_words: Option<usize>,
+    // plans of individual layers (of the planned network)
+    pub layers: Vec<LayerPlan>,
+    // outputs are scaled by 2^-out_shift (w.r.t. the original network)
+    pub out_shift: usize,
+}
+
+impl LengthPlan {
+
+    /// Plan ciphertext lengths of Neural Network for inputs within `in_range`
+    /// * if `max_words` is given, rounding is inserted to keep all values within the budget
+    ///   (sign activation cannot follow inserted rounding, since its output does not scale)
+    pub fn new(
+        nn: &NeuralNetwork,
+        in_range: (i64, i64),
+        max_words: Option<usize>,
+    ) -> Result<LengthPlan, Box<dyn Error>> {
+        nn.validate()?;
+        if in_range.0 > in_range.1 {
+            return Err(format!("Empty input range [{}, {}].", in_range.0, in_range.1).into());
+        }
+
+        let in_bounds = vec![(in_range.0 as i128, in_range.1 as i128); nn.n_inputs];
+        let in_bits = bits_of(&in_bounds)?;
+        if let Some(mw) = max_words {
+            if in_bits + 1 > mw {
+                return Err(format!("Inputs of {} bits do not fit the budget of {} words.", in_bits, mw).into());
+            }
+        }
+
+        let mut layers = Vec::new();
+        let mut plans = Vec::new();
+        let mut shift = 0usize;
+        let mut vals = in_bounds;
+
+        for (li, layer) in nn.layers.iter().enumerate() {
+            let err_li = |e: Box<dyn Error>| -> Box<dyn Error> {format!("Layer {}: {}", li, e).into()};
+
+            let mut sc_layer = scale_layer(layer, shift).map_err(err_li)?;
+            let (mut acc_bits, mut out) = layer_bounds(&sc_layer, &vals).map_err(err_li)?;
+
+            // insert rounding if over budget: the least position that fits
+            if let Some(mw) = max_words {
+                if acc_bits.max(bits_of(&out)?) + 1 > mw {
+                    let mut found = None;
+                    for pos in 1..=bits_of(&vals)? {
+                        let vals_r: Vec<Bounds> = vals.iter().map(|(lo, hi)| (round_at(*lo, pos), round_at(*hi, pos))).collect();
+                        let layer_r = scale_layer(layer, shift + pos).map_err(err_li)?;
+                        let (acc_bits_r, out_r) = layer_bounds(&layer_r, &vals_r).map_err(err_li)?;
+                        // n.b., bits + 1 words
+                        if acc_bits_r.max(bits_of(&out_r)?) < mw {
+                            found = Some((pos, vals_r, layer_r, acc_bits_r, out_r));
+                            break;
+                        }
+                    }
+                    let (pos, vals_r, layer_r, acc_bits_r, out_r) = found
+                        .ok_or_else(|| format!("Layer {} does not fit the budget of {} words even with rounding.", li, mw))?;
+
+                    layers.push(Layer::Rescale {pos});
+                    plans.push(LayerPlan {
+                        inserted: true,
+                        acc_bits: bits_of(&vals_r)?,
+                        out_bounds: to_i64(&vals_r),
+                        out_bits: vals_r.iter().map(|v| bits_of(&[*v])).collect::<Result<Vec<usize>, Box<dyn Error>>>()?,
+                    });
+
+                    shift += pos;
+                    sc_layer = layer_r;
+                    acc_bits = acc_bits_r;
+                    out = out_r;
+                }
+            }
+
+            layers.push(sc_layer);
+            plans.push(LayerPlan {
+                inserted: false,
+                acc_bits,
+                out_bounds: to_i64(&out),
+                out_bits: out.iter().map(|v| bits_of(&[*v])).collect::<Result<Vec<usize>, Box<dyn Error>>>()?,
+            });
+            vals = out;
+        }
+
+        let planned = NeuralNetwork {layers, n_inputs: nn.n_inputs, argmax: nn.argmax};
+        planned.validate()?;
+
+        Ok(LengthPlan {
+            nn: planned,
+            in_range,
+            in_bits,
+            max_words,
+            layers: plans,
+            out_shift: shift,
+        })
+    }
+
+    /// The largest number of words of a value (after trimming) in the whole network
+    pub fn words(&self) -> usize {
+        self.layers.iter().map(|lp| lp.words()).fold(self.in_bits + 1, usize::max)
+    }
+
+    /// Evaluate planned Neural Network, values are trimmed after each layer
+    /// * inputs must be within the declared range
+    pub fn eval<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        inputs: &[T],
+    ) -> Vec<T> {
+        let in_bits = vec![self.in_bits; inputs.len()];
+        let mut il = trim_all(pc, inputs, &in_bits);
+        let mut ol: Vec<T> = Vec::new();
+
+        measure_duration!(
+            ["Planned Neural Network evaluation over {} (max {} words)", std::any::type_name::<T>(), self.words()],
+            [
+                for (layer, lp) in self.nn.layers.iter().zip(self.layers.iter()) {
+                    self.nn.eval_layer::<T>(pc, layer, &il, &mut ol);
+                    il = trim_all(pc, &ol, &lp.out_bits);
+                }
+            ]
+        );
+
+        self.nn.finish(pc, il)
+    }
+}
+
+impl fmt::Display for LengthPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "inputs in [{}, {}]: {} bits", self.in_range.0, self.in_range.1, self.in_bits)?;
+        for (li, (layer, lp)) in self.nn.layers.iter().zip(self.layers.iter()).enumerate() {
+            let (lo, hi) = lp.out_bounds.iter().fold((i64::MAX, i64::MIN), |(lo, hi), (l, h)| (lo.min(*l), hi.max(*h)));
+            writeln!(f, "layer {:>2} {:<8} {:>3} outputs in [{}, {}]: acc {} bits, out {} bits, {} words{}",
+                li, layer_name(layer), lp.out_bits.len(), lo, hi,
+                lp.acc_bits, lp.out_bits.iter().max().unwrap_or(&0), lp.words(),
+                if lp.inserted {" (inserted)"} else {""})?;
+        }
+        write!(f, "max {} words", self.words())?;
+        if let Some(mw) = self.max_words {write!(f, " (budget {})", mw)?;}
+        if self.out_shift > 0 {write!(f, ", outputs scaled by 2^-{}", self.out_shift)?;}
+        Ok(())
+    }
+}
+
+/// Trim values to their bit lengths
+pub(crate) fn trim_all<T: Clone + ParmArithmetics + Send + Sync>(
+    pc: &ParmesanCloudovo,
+    vals: &[T],
+    bits: &[usize],
+) -> Vec<T> {
+    // parallel iterators
+    #[cfg(not(feature = "seq_analyze"))]
+    let v_b_iter = vals.par_iter().zip(bits.par_iter());
+    // sequential iterators
+    #[cfg(feature = "seq_analyze")]
+    let v_b_iter = vals.iter().zip(bits.iter());
+
+    v_b_iter.map(|(vi, bi)| ParmArithmetics::trim(pc, vi, *bi)).collect()
+}
+
+/// Layer adjusted to inputs scaled by `2^-shift`: biases & clamping thresholds are scaled, too
+fn scale_layer(layer: &Layer, shift: usize) -> Result<Layer, Box<dyn Error>> {
+    if shift == 0 {return Ok(layer.clone());}
+
+    let scale_perc = |perc: &Perceptron| -> Result<Perceptron, Box<dyn Error>> {
+        let a = match (perc.t, perc.a) {
+            (PercType::ACT, Activation::Sign) => return Err("Sign activation cannot follow inserted rounding.".into()),
+            (PercType::ACT, Activation::HardTanh {frac}) if frac >= shift => Activation::HardTanh {frac: frac - shift},
+            (PercType::ACT, Activation::HardSigmoid {frac}) if frac > shift => Activation::HardSigmoid {frac: frac - shift},
+            (PercType::ACT, Activation::HardTanh {..}) | (PercType::ACT, Activation::HardSigmoid {..}) => {
+                return Err(format!("Clamping activation cannot follow rounding by {} bits.", shift).into());
+            },
+            (_, a) => a,
+        };
+        Ok(Perceptron {
+            t: perc.t,
+            w: perc.w.clone(),
+            b: round_at(perc.b as i128, shift) as i64,
+            a,
+        })
+    };
+
+    let mut sc_layer = layer.clone();
+    match &mut sc_layer {
+        Layer::Dense {perceptrons: percs} | Layer::Conv1d {kernels: percs, ..} | Layer::Conv2d {kernels: percs, ..} => {
+            *percs = percs.iter().map(scale_perc).collect::<Result<Vec<Perceptron>, Box<dyn Error>>>()?;
+        },
+        _ => {},
+    }
+    Ok(sc_layer)
+}
+
+/// Bounds of accumulated values (bit length only) & of outputs of a layer
+pub(crate) fn layer_bounds(
+    layer: &Layer,
+    vals: &[Bounds],
+) -> Result<(usize, Vec<Bounds>), Box<dyn Error>> {
+    let mut acc = Vec::new();
+    let mut out = Vec::new();
+
+    match layer {
+        Layer::Dense {perceptrons} => {
+            for perc in perceptrons {
+                let terms: Vec<Bounds> = perc.w.iter().zip(vals.iter()).map(|(wi, vi)| mul_bounds(*wi, vi)).collect();
+                let (acc_p, out_p) = perc_bounds(perc, &terms);
+                acc.push(acc_p);
+                out.push(out_p);
+            }
+        },
+        Layer::Rescale {pos} => {
+            out = vals.iter().map(|(lo, hi)| (round_at(*lo, *pos), round_at(*hi, *pos))).collect();
+        },
+        Layer::Conv1d {kernels, ..} | Layer::Conv2d {kernels, ..} => {
+            let g = layer.grid(vals.len())?.ok_or("Convolution without geometry.")?;
+            let kk = g.kh * g.kw;
+            for kern in kernels {
+                for oy in 0..g.ho {
+                    for ox in 0..g.wo {
+                        // n.b., padding contributes zeros
+                        let terms: Vec<Bounds> = (0..g.c * kk).map(|cj| {
+                            let (c, j) = (cj / kk, cj % kk);
+                            g.in_idx(c, oy, ox, j / g.kw, j % g.kw).map_or((0, 0), |i| mul_bounds(kern.w[cj], &vals[i]))
+                        }).collect();
+                        let (acc_p, out_p) = perc_bounds(kern, &terms);
+                        acc.push(acc_p);
+                        out.push(out_p);
+                    }
+                }
+            }
+        },
+        Layer::Pool1d {op, ..} | Layer::Pool2d {op, ..} => {
+            let g = layer.grid(vals.len())?.ok_or("Pooling without geometry.")?;
+            let kk = g.kh * g.kw;
+            for c in 0..g.c {
+                for oy in 0..g.ho {
+                    for ox in 0..g.wo {
+                        let win: Vec<Bounds> = (0..kk).filter_map(|j| g.in_idx(c, oy, ox, j / g.kw, j % g.kw)).map(|i| vals[i]).collect();
+                        let sum = win.iter().fold((0, 0), |(lo, hi), (l, h)| (lo + l, hi + h));
+                        let v = match op {
+                            PoolOp::Sum => sum,
+                            PoolOp::Avg => (round_at(sum.0, kk.trailing_zeros() as usize), round_at(sum.1, kk.trailing_zeros() as usize)),
+                            PoolOp::Max => max_bounds(&win),
+                        };
+                        // n.b., maximum does not accumulate
+                        acc.push(if matches!(op, PoolOp::Max) {v} else {sum});
+                        out.push(v);
+                    }
+                }
+            }
+        },
+        Layer::Flatten => {
+            out = vals.to_vec();
+        },
+    }
+
+    // n.b., layers without accumulation
+    let acc_bits = bits_of(if acc.is_empty() {&out} else {&acc})?;
+    Ok((acc_bits, out))
+}
+
+/// Bounds of perceptron: accumulated value & output
+fn perc_bounds(perc: &Perceptron, terms: &[Bounds]) -> (Bounds, Bounds) {
+    let b = perc.b as i128;
+    let acc = match perc.t {
+        PercType::MAX => {
+            let (lo, hi) = max_bounds(terms);
+            (lo + b, hi + b)
+        },
+        PercType::LIN | PercType::ACT => {
+            terms.iter().fold((b, b), |(lo, hi), (l, h)| (lo + l, hi + h))
+        },
+    };
+    let out = match perc.t {
+        // n.b., all activation functions are non-decreasing
+        PercType::ACT => (act_bound(&perc.a, acc.0), act_bound(&perc.a, acc.1)),
+        _ => acc,
+    };
+    (acc, out)
+}
+
+/// Activation function over bounds (cf. `Activation::eval`)
+pub(crate) fn act_bound(a: &Activation, x: i128) -> i128 {
+    match a {
+        Activation::Sign                => x.signum(),
+        Activation::Relu                => x.max(0),
+        Activation::LeakyRelu {shift}   => x.max(round_at(x, *shift)),
+        Activation::HardTanh {frac}     => x.clamp(-(1 << frac), 1 << frac),
+        Activation::HardSigmoid {frac}  => (round_at(x, 2) + (1 << (frac - 1))).clamp(0, 1 << frac),
+        Activation::Identity            => x,
+    }
+}
+
+/// Bounds of weighted value
+fn mul_bounds(w: i32, (lo, hi): &Bounds) -> Bounds {
+    let (l, h) = (w as i128 * lo, w as i128 * hi);
+    (l.min(h), l.max(h))
+}
+
+/// Bounds of maximum (zero if empty, cf. `NeuralNetwork::max_pool_recursion`)
+pub(crate) fn max_bounds(vals: &[Bounds]) -> Bounds {
+    vals.iter().copied().reduce(|(lo, hi), (l, h)| (lo.max(l), hi.max(h))).unwrap_or((0, 0))
+}
+
+/// Rounding at position as `ParmArithmetics::rescale`: `⌊x / 2^pos + 1/2⌋`
+fn round_at(x: i128, pos: usize) -> i128 {
+    if pos == 0 {x} else {(x + (1 << (pos - 1))) >> pos}
+}
+
+/// The least `bits` such that `|v| < 2^bits` for all values
+pub(crate) fn bits_of(vals: &[Bounds]) -> Result<usize, Box<dyn Error>> {
+    let m = vals.iter().map(|(lo, hi)| lo.abs().max(hi.abs())).max().unwrap_or(0);
+    let bits = (128 - m.leading_zeros()) as usize;
+    if bits > BOUND_MAX_BITS {
+        return Err(format!("Worst-case value of {} bits exceeds {} bits.", bits, BOUND_MAX_BITS).into());
+    }
+    Ok(bits)
+}
+
+/// Bounds as `i64` (n.b., checked by `bits_of`)
+fn to_i64(vals: &[Bounds]) -> Vec<(i64, i64)> {
+    vals.iter().map(|(lo, hi)| (*lo as i64, *hi as i64)).collect()
+}
+
+/// Name of layer type (as in files)
+fn layer_name(layer: &Layer) -> &'static str {
+    match layer {
+        Layer::Dense {..}   => "dense",
+        Layer::Rescale {..} => "rescale",
+        Layer::Conv1d {..}  => "conv1d",
+        Layer::Conv2d {..}  => "conv2d",
+        Layer::Pool1d {..}  => "pool1d",
+        Layer::Pool2d {..}  => "pool2d",
+        Layer::Flatten      => "flatten",
+    }
+}
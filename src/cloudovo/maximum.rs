@@ -13,6 +13,7 @@ use rayon::prelude::*;
 use colored::Colorize;
 
 use crate::ciphertexts::{ParmCiphertext,ParmCiphertextImpl,ParmEncrWord};
+use crate::userovo::encryption::bit_len_64;
 use super::{pbs,signum};
 
 /// Implementation of parallel maximum using signum
@@ -22,7 +23,7 @@ pub fn max_impl(
     y:  &ParmCiphertext,
 ) -> Result<ParmCiphertext, Box<dyn Error>> {
 
-    let mut m: ParmCiphertext;
+    let m: ParmCiphertext;
 
     measure_duration!(
         ["Maximum ({}-bit)", x.len()],
@@ -30,60 +31,115 @@ pub fn max_impl(
             // r = x - y
             let r: ParmCiphertext = ParmArithmetics::sub(pc, x, y);   // new sgn_recursion_raw requires fresh samples
 
-            // s = nonneg(r)
-            // returns one sample .. res in {-15, ..., 15} (to be bootstrapped with nonneg)
-            let s_raw: ParmCiphertext = signum::sgn_recursion_raw(
-                pc,
-                &r,
-                true,
-            )?;
-            // bootstrap whether >= 0 .. res in {0, 1}
-            let s: ParmEncrWord = pbs::nonneg__pi_5(
-                pc,
-                &s_raw[0],
-            );
-
-            // align inputs
-            let mut xa = x.clone();
-            let mut ya = y.clone();
-            for _ in 0..((y.len() as i64) - (x.len() as i64)) {
-                xa.push(ParmEncrWord::encrypt_word_triv(&pc.pub_keys, 0));
-            }
-            for _ in 0..((x.len() as i64) - (y.len() as i64)) {
-                ya.push(ParmEncrWord::encrypt_word_triv(&pc.pub_keys, 0));
-            }
+            // s = nonneg(r) .. res in {0, 1}
+            let s: ParmEncrWord = signum::nonneg_impl(pc, &r).expect("nonneg_impl failed.");
 
-            m = ParmCiphertext::triv(xa.len(), &pc);
-
-            // parallel iterators
-            #[cfg(not(feature = "seq_analyze"))]
-            let m_x_y_iter = m.par_iter_mut().zip(xa.par_iter().zip(ya.par_iter()));
-            // sequential iterators
-            #[cfg(feature = "seq_analyze")]
-            let m_x_y_iter = m.iter_mut().zip(xa.iter().zip(ya.iter()));
-
-            // calc x and y selectors
-            m_x_y_iter.for_each(| (mi, (xi, yi)) | {
-                // 6 yi
-                let mut s_2xi_6yi;
-                // check whether direct multiplication of yi by 6 can be applied
-                // (altogether 6yi + 2xi + s gives QW = 6^2 + 2^2 + 1^2 = 41)
-                if pc.params.quad_weight >= 41 {
-                    s_2xi_6yi = yi.mul_const(6);
-                } else {
-                    s_2xi_6yi = pbs::f_1__pi_5__with_val(pc, yi, 6);
-                }
-                // 2 xi
-                let xi_2 = xi.mul_const(2);
-                s_2xi_6yi.add_inplace(&xi_2);
-                // s + 2 xi + 6 yi
-                s_2xi_6yi.add_inplace(&s);
-
-                // mi = ReLU+(xi + 2s)
-                *mi = pbs::max_s_2x_6y__pi_5(pc, &s_2xi_6yi);   // ti
-            });
+            m = select_impl(pc, &s, x, y)?;
         ]
     );
 
     Ok(m)
 }
+
+/// Implementation of oblivious selection: `s ? X : Y`
+/// * selector `s` is a fresh sample in `{0, 1}`
+/// * inputs are aligned to the same length, each word is selected by a single bootstrap
+pub fn select_impl(
+    pc: &ParmesanCloudovo,
+    s:  &ParmEncrWord,
+    x:  &ParmCiphertext,
+    y:  &ParmCiphertext,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    // align inputs
+    let mut xa = x.clone();
+    let mut ya = y.clone();
+    for _ in 0..((y.len() as i64) - (x.len() as i64)) {
+        xa.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));
+    }
+    for _ in 0..((x.len() as i64) - (y.len() as i64)) {
+        ya.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));
+    }
+
+    let mut m = ParmCiphertext::triv(xa.len(), pc);
+
+    // parallel iterators
+    #[cfg(not(feature = "seq_analyze"))]
+    let m_x_y_iter = m.par_iter_mut().zip(xa.par_iter().zip(ya.par_iter()));
+    // sequential iterators
+    #[cfg(feature = "seq_analyze")]
+    let m_x_y_iter = m.iter_mut().zip(xa.iter().zip(ya.iter()));
+
+    // calc x and y selectors
+    m_x_y_iter.for_each(| (mi, (xi, yi)) | {
+        // 6 yi
+        let mut s_2xi_6yi;
+        // check whether direct multiplication of yi by 6 can be applied
+        // (altogether 6yi + 2xi + s gives QW = 6^2 + 2^2 + 1^2 = 41)
+        if pc.params.quad_weight >= 41 {
+            s_2xi_6yi = yi.mul_const(6);
+        } else {
+            s_2xi_6yi = pbs::f_1__pi_5__with_val(pc, yi, 6);
+        }
+        // 2 xi
+        let xi_2 = xi.mul_const(2);
+        s_2xi_6yi.add_inplace(&xi_2);
+        // s + 2 xi + 6 yi
+        s_2xi_6yi.add_inplace(s);
+
+        // mi = ReLU+(xi + 2s)
+        *mi = pbs::max_s_2x_6y__pi_5(pc, &s_2xi_6yi);   // ti
+    });
+
+    Ok(m)
+}
+
+/// Implementation of arg-max: index of `max{X_0, ..., X_{n-1}}` (the first one on ties)
+/// * tournament tree of `⌈log_2 n⌉` rounds, matches of a round are evaluated concurrently
+/// * each value carries its index (a trivial ciphertext at first), a single comparison `s = nonneg(X_l - X_r)`
+///   then selects both the winner and its index
+pub fn argmax_impl(
+    pc: &ParmesanCloudovo,
+    xs: &[ParmCiphertext],
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    if xs.is_empty() {
+        return Err("Arg-max of an empty vector.".into());
+    }
+
+    // indexes as trivial ciphertexts
+    let idx_len = bit_len_64((xs.len() - 1) as u64);
+    let mut cands: Vec<(ParmCiphertext, ParmCiphertext)> = xs.iter().enumerate().map(|(i, xi)| {
+        let idx: ParmCiphertext = (0..idx_len).map(|j| ParmEncrWord::encrypt_word_triv(pc.pub_keys, ((i >> j) & 1) as i32)).collect();
+        (xi.clone(), idx)
+    }).collect();
+
+    measure_duration!(
+        ["Arg-max ({} values)", xs.len()],
+        [
+            while cands.len() > 1 {
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let match_iter = cands.par_chunks(2);
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let match_iter = cands.chunks(2);
+
+                cands = match_iter.map(|lr| {
+                    // odd one advances without a match
+                    if lr.len() == 1 {return lr[0].clone();}
+                    let ((xl, il), (xr, ir)) = (&lr[0], &lr[1]);
+
+                    // s = nonneg(X_l - X_r) .. ties go left, i.e., to the lower index
+                    let r: ParmCiphertext = ParmArithmetics::sub(pc, xl, xr);
+                    let s: ParmEncrWord = signum::nonneg_impl(pc, &r).expect("nonneg_impl failed.");
+
+                    (
+                        select_impl(pc, &s, xl, xr).expect("select_impl failed."),
+                        select_impl(pc, &s, il, ir).expect("select_impl failed."),
+                    )
+                }).collect();
+            }
+        ]
+    );
+
+    Ok(cands.swap_remove(0).1)
+}
@@ -6,6 +6,14 @@ pub mod signum;
 pub mod maximum;
 pub mod multiplication;
 pub mod squaring;
+pub mod square_root;
 pub mod rounding;
+pub mod polynomial;
+pub mod fixed_point;
+pub mod floating_point;
 
 pub mod neural_network;
+pub mod quantization;
+pub mod length_plan;
+pub mod private_network;
+pub mod decision_tree;
@@ -19,6 +19,7 @@ use colored::Colorize;
 
 use crate::ciphertexts::{ParmCiphertext,ParmCiphertextImpl,ParmEncrWord};
 use super::pbs;
+use super::addition;
 
 
 // =============================================================================
@@ -85,6 +86,84 @@ pub fn mul_impl(
     }
 }
 
+/// Implementation of inner product of two encrypted vectors: `Σ X_i × Y_i`
+/// * mul-arrays of all pairs are filled in parallel (schoolbook-like, cf. `fill_mulary`)
+/// * all their rows are reduced by a single multi-operand addition (instead of finishing each product first)
+pub fn inner_prod_impl(
+    pc: &ParmesanCloudovo,
+    xs: &[ParmCiphertext],
+    ys: &[ParmCiphertext],
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    if xs.len() != ys.len() {
+        return Err(format!("Inner product of vectors of different lengths: {} and {}.", xs.len(), ys.len()).into());
+    }
+
+    let mut mularies: Vec<Vec<ParmCiphertext>> = vec![Vec::new(); xs.len()];
+
+    measure_duration!(
+        ["Inner product ({} items)", xs.len()],
+        [
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let m_xy_iter = mularies.par_iter_mut().zip(xs.par_iter().zip(ys.par_iter()));
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let m_xy_iter = mularies.iter_mut().zip(xs.iter().zip(ys.iter()));
+
+            m_xy_iter.for_each(| (mi, (xi, yi)) | {
+                let mut xi_in = xi.clone();
+                let mut yi_in = yi.clone();
+
+                // align lengths of x_i & y_i
+                while xi_in.len() < yi_in.len() {xi_in.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));}
+                while yi_in.len() < xi_in.len() {yi_in.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));}
+
+                *mi = fill_mulary(pc, &xi_in, &yi_in).expect("fill_mulary failed.");
+            });
+
+            // merge all mul-arrays & reduce at once
+            let mulary: Vec<ParmCiphertext> = mularies.concat();
+            let res = addition::sum_impl(pc, &mulary)?;
+        ]
+    );
+
+    Ok(res)
+}
+
+/// Implementation of matrix-vector product: `M × X`, where `M` is given row-wise
+/// * rows are evaluated in parallel, each as an inner product, cf. `inner_prod_impl`
+pub fn mat_vec_impl(
+    pc: &ParmesanCloudovo,
+    m: &[Vec<ParmCiphertext>],
+    x: &[ParmCiphertext],
+) -> Result<Vec<ParmCiphertext>, Box<dyn Error>> {
+    for mi in m {
+        if mi.len() != x.len() {
+            return Err(format!("Matrix row of length {} does not match vector of length {}.", mi.len(), x.len()).into());
+        }
+    }
+
+    let mut res = vec![ParmCiphertext::empty(); m.len()];
+
+    measure_duration!(
+        ["Matrix-vector product ({}×{})", m.len(), x.len()],
+        [
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let r_m_iter = res.par_iter_mut().zip(m.par_iter());
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let r_m_iter = res.iter_mut().zip(m.iter());
+
+            r_m_iter.for_each(| (ri, mi) | {
+                *ri = inner_prod_impl(pc, mi, x).expect("inner_prod_impl failed.");
+            });
+        ]
+    );
+
+    Ok(res)
+}
+
 /// Karatsuba multiplication
 fn mul_karatsuba(
     pc: &ParmesanCloudovo,
@@ -218,7 +297,7 @@ fn mul_schoolbook(
             // calc multiplication array
             let mulary = fill_mulary(pc, x, y)?;
 
-            let res = reduce_mulsquary(pc, &mulary);
+            let res = reduce_mulsquary(pc, &mulary)?;
         ]
     );
 
@@ -244,7 +323,7 @@ fn mul_1word(
 }
 
 /// Fill multiplication array (for schoolbook multiplication)
-fn fill_mulary(
+pub(crate) fn fill_mulary(
     pc: &ParmesanCloudovo,
     x:  &ParmCiphertext,
     y:  &ParmCiphertext,
@@ -319,19 +398,19 @@ pub fn mul_lwe(
 }
 
 /// Reduce mul/squ-ary with addition
+/// * rows are summed up by a parallel tree of additions, cf. `addition::sum_impl`
 pub fn reduce_mulsquary(
     pc: &ParmesanCloudovo,
     mulary: &Vec<ParmCiphertext>,
-) -> ParmCiphertext {
-    let mut intmd = vec![ParmCiphertext::empty(); 2];
-    let mut idx = 0usize;
-    intmd[idx] = ParmArithmetics::add(pc, &mulary[0], &mulary[1]);
-
-    //TODO add parallelism except for the longest number (so that the result is as short as possible)
-    for i in 2..mulary.len() {
-        idx ^= 1;
-        intmd[idx] = ParmArithmetics::add(pc, &intmd[idx ^ 1], &mulary[i]);
-    }
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    let res;
+
+    measure_duration!(
+        ["Mul/squ-ary reduction ({} rows)", mulary.len()],
+        [
+            res = addition::sum_impl(pc, mulary)?;
+        ]
+    );
 
-    intmd[idx].clone()
+    Ok(res)
 }
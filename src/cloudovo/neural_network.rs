@@ -37,6 +37,8 @@
 //!                 o-----------o
 //!```
 
+use std::error::Error;
+
 #[allow(unused_imports)]
 use colored::Colorize;
 
@@ -47,24 +49,109 @@ use crate::*;
 
 use rand::{distributions::{Distribution,Standard},Rng};
 
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+
 use crate::ParmesanCloudovo;
 use crate::arithmetics::ParmArithmetics;
 
 /// Perceptron type:
 /// * maximum,
 /// * linear combination,
-/// * linear combination with activation function (signum).
+/// * linear combination with activation function (cf. `Activation`).
 /// ReLU as activation function can be constructed in two layers as MAX{LIN, 0}.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PercType {
     // maximum of weighted inputs + bias
     MAX,
     // sum of weighted inputs + bias (affine mapping; useful as an input for MAX perceptron)
     LIN,
-    // LIN with a non-linear activation function applied (signum)
+    // LIN with a non-linear activation function applied
     ACT,
 }
 
+/// Activation function of `ACT` perceptron, e.g., `a: {act: leaky_relu, shift: 2}`
+/// * `frac` is the number of fractional bits of the input, i.e., `1` is represented as `2^frac`
+///   (n.b., ignored by floating-point networks, set by quantization)
+///
+/// ```text
+///     sign:           sgn(x)                              .. {-1, 0, 1}
+///     relu:           max(x, 0)
+///     leaky_relu:     max(x, round(x / 2^shift))
+///     hard_tanh:      clamp(x, -1, 1)
+///     hard_sigmoid:   clamp(round(x / 4) + 1/2, 0, 1)
+///     identity:       x
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "act", rename_all = "snake_case")]
+pub enum Activation {
+    Sign,
+    #[default]
+    Relu,
+    LeakyRelu {shift: usize},
+    HardTanh {#[serde(default)] frac: usize},
+    HardSigmoid {#[serde(default)] frac: usize},
+    Identity,
+}
+
+impl Activation {
+    /// Evaluate activation function: `sign` and `relu` by `sgn` and `relu`, respectively,
+    /// others are composed of `max`, `relu` and `rescale`, which are exact for `i64`, too
+    /// * clamp to `[lo, hi]` is evaluated as `lo + relu(x - lo) - relu(x - hi)`
+    pub fn eval<T: ParmArithmetics>(
+        &self,
+        pc: &ParmesanCloudovo,
+        x: &T,
+    ) -> T {
+        match self {
+            Activation::Sign        => ParmArithmetics::sgn(pc, x),
+            Activation::Relu        => ParmArithmetics::relu(pc, x),
+            Activation::LeakyRelu {shift} => {
+                let x_sh = ParmArithmetics::rescale(pc, x, *shift);
+                ParmArithmetics::max(pc, x, &x_sh)
+            },
+            Activation::HardTanh {frac} => {
+                let one = 1i64 << frac;
+                Self::clamp(pc, x, -one, one)
+            },
+            Activation::HardSigmoid {frac} => {
+                let one = 1i64 << frac;
+                let x_4 = ParmArithmetics::rescale(pc, x, 2);
+                let y = ParmArithmetics::add_const(pc, &x_4, one / 2);
+                Self::clamp(pc, &y, 0, one)
+            },
+            Activation::Identity    => ParmArithmetics::add_const(pc, x, 0),
+        }
+    }
+
+    /// Check parameters of activation function
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        match self {
+            Activation::LeakyRelu {shift} if *shift == 0 => Err("Leaky ReLU requires a positive shift.".into()),
+            Activation::HardTanh {frac} if *frac > 31 => Err(format!("Hard tanh with {} fractional bits not supported (max 31).", frac).into()),
+            Activation::HardSigmoid {frac} if *frac == 0 || *frac > 31 => Err(format!("Hard sigmoid with {} fractional bits not supported (1..=31).", frac).into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Clamp to `[lo, hi]`: `lo + relu(x - lo) - relu(x - hi)`
+    fn clamp<T: ParmArithmetics>(
+        pc: &ParmesanCloudovo,
+        x: &T,
+        lo: i64,
+        hi: i64,
+    ) -> T {
+        let r_lo = ParmArithmetics::relu(pc, &ParmArithmetics::add_const(pc, x, -lo));
+        let r_hi = ParmArithmetics::relu(pc, &ParmArithmetics::add_const(pc, x, -hi));
+        let diff = ParmArithmetics::sub(pc, &r_lo, &r_hi);
+        ParmArithmetics::add_const(pc, &diff, lo)
+    }
+}
+
 /// Perceptron
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Perceptron {
     // perceptron type
     pub t: PercType,
@@ -72,23 +159,270 @@ pub struct Perceptron {
     pub w: Vec<i32>,
     // bias
     pub b: i64,
+    // activation function (applies to ACT only; ReLU if omitted)
+    #[serde(default)]
+    pub a: Activation,
+}
+
+/// Pooling operation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolOp {
+    // sum of the window
+    Sum,
+    // average of the window (n.b., window area must be a power of two; rounded by `rescale`)
+    Avg,
+    // maximum of the window
+    Max,
+}
+
+/// Layer:
+/// * dense layer of perceptrons, each connected to all outputs of the preceding layer,
+/// * rescaling of all outputs of the preceding layer by `2^-pos` (with rounding),
+///   e.g., after a layer with quantized weights (cf. `quantization`),
+/// * 1D/2D convolution with shared kernels (given as perceptrons with weights ordered as `[channel][ky][kx]`),
+///   stride & zero padding,
+/// * 1D/2D sum/average/maximum pooling,
+/// * flatten.
+///
+/// Values between layers are kept flat, channel-major, i.e., as `[channel][y][x]` (or `[channel][x]` in 1D),
+/// hence outputs of a convolution/pooling layer are `[kernel/channel][y][x]`, and flatten only marks the transition
+/// to dense layers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "layer", rename_all = "snake_case")]
+pub enum Layer {
+    Dense {
+        perceptrons: Vec<Perceptron>,
+    },
+    Rescale {
+        pos: usize,
+    },
+    Conv1d {
+        in_channels: usize,
+        kernel_size: usize,
+        stride: usize,
+        padding: usize,
+        kernels: Vec<Perceptron>,
+    },
+    Conv2d {
+        in_channels: usize,
+        in_width: usize,
+        kernel_size: usize,
+        stride: usize,
+        padding: usize,
+        kernels: Vec<Perceptron>,
+    },
+    Pool1d {
+        op: PoolOp,
+        in_channels: usize,
+        size: usize,
+        stride: usize,
+    },
+    Pool2d {
+        op: PoolOp,
+        in_channels: usize,
+        in_width: usize,
+        size: usize,
+        stride: usize,
+    },
+    Flatten,
 }
 
-/// Layer
-pub type Layer = Vec<Perceptron>;
+impl Layer {
+    /// Number of outputs for given number of inputs (checks the geometry of convolution/pooling)
+    pub fn out_len(&self, in_len: usize) -> Result<usize, Box<dyn Error>> {
+        Ok(match self {
+            Layer::Dense {perceptrons} => perceptrons.len(),
+            Layer::Rescale {..} | Layer::Flatten => in_len,
+            Layer::Conv1d {kernels, ..} | Layer::Conv2d {kernels, ..} => {
+                let g = self.grid(in_len)?.ok_or("Convolution without geometry.")?;
+                kernels.len() * g.ho * g.wo
+            },
+            Layer::Pool1d {..} | Layer::Pool2d {..} => {
+                let g = self.grid(in_len)?.ok_or("Pooling without geometry.")?;
+                g.c * g.ho * g.wo
+            },
+        })
+    }
+
+    /// Geometry of convolution/pooling layer (1D as 2D of height 1)
+    pub(crate) fn grid(&self, in_len: usize) -> Result<Option<Grid>, Box<dyn Error>> {
+        // channels, width (if 2D), kernel size, stride, padding
+        let (c, w_2d, k, s, p) = match self {
+            Layer::Conv1d {in_channels, kernel_size, stride, padding, ..} => (*in_channels, None, *kernel_size, *stride, *padding),
+            Layer::Conv2d {in_channels, in_width, kernel_size, stride, padding, ..} => (*in_channels, Some(*in_width), *kernel_size, *stride, *padding),
+            Layer::Pool1d {in_channels, size, stride, ..} => (*in_channels, None, *size, *stride, 0),
+            Layer::Pool2d {in_channels, in_width, size, stride, ..} => (*in_channels, Some(*in_width), *size, *stride, 0),
+            _ => return Ok(None),
+        };
+        if c == 0 || k == 0 || s == 0 || w_2d == Some(0) {
+            return Err("Channels, width, kernel size and stride must be positive.".into());
+        }
+
+        let (h, w, kh, ph) = match w_2d {
+            None    => (1, in_len / c, 1, 0),
+            Some(w) => (in_len / (c * w), w, k, p),
+        };
+        if c * h * w != in_len {
+            return Err(format!("Input of length {} cannot be split into {} channels of {}×{}.", in_len, c, h, w).into());
+        }
+        if h + 2 * ph < kh || w + 2 * p < k {
+            return Err(format!("Kernel {}×{} is larger than padded input {}×{}.", kh, k, h + 2 * ph, w + 2 * p).into());
+        }
+
+        Ok(Some(Grid {
+            c, h, w, kh, kw: k, s, ph, pw: p,
+            ho: (h + 2 * ph - kh) / s + 1,
+            wo: (w + 2 * p - k) / s + 1,
+        }))
+    }
+}
+
+/// Geometry of convolution/pooling: input `c × h × w`, kernel `kh × kw`, stride, padding, output `ho × wo`
+pub(crate) struct Grid {
+    pub(crate) c: usize, pub(crate) h: usize, pub(crate) w: usize,
+    pub(crate) kh: usize, pub(crate) kw: usize,
+    pub(crate) s: usize,
+    pub(crate) ph: usize, pub(crate) pw: usize,
+    pub(crate) ho: usize, pub(crate) wo: usize,
+}
+
+impl Grid {
+    /// Index of input at kernel offset `(ky, kx)` of output `(oy, ox)` in channel `c` (None if in padding)
+    pub(crate) fn in_idx(&self, c: usize, oy: usize, ox: usize, ky: usize, kx: usize) -> Option<usize> {
+        let y = (oy * self.s + ky).checked_sub(self.ph).filter(|y| *y < self.h)?;
+        let x = (ox * self.s + kx).checked_sub(self.pw).filter(|x| *x < self.w)?;
+        Some((c * self.h + y) * self.w + x)
+    }
+
+    /// Whether input at `(y, x)` is at kernel offset `(ky, kx)` of some output
+    fn is_used(&self, y: usize, x: usize, ky: usize, kx: usize) -> bool {
+        let ty = y + self.ph;
+        let tx = x + self.pw;
+        ty >= ky && (ty - ky).is_multiple_of(self.s) && (ty - ky) / self.s < self.ho
+            && tx >= kx && (tx - kx).is_multiple_of(self.s) && (tx - kx) / self.s < self.wo
+    }
+}
 
 /// Neural Network
+/// * can be loaded from / saved to a JSON or YAML file (cf. `assets/nn-*`), e.g.:
+///
+/// ```yaml
+/// n_inputs: 3
+/// layers:
+/// - layer: dense
+///   perceptrons:
+///   - {t: MAX, w: [1, -2, -2], b: 2}
+///   - {t: LIN, w: [1, 3, -1], b: -5}
+/// - layer: rescale
+///   pos: 2
+/// ```
+/// * with `argmax: true`, only the (encrypted) index of the maximal output is returned, i.e., the winning class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NeuralNetwork {
     //  NN consists of layers, evaluated one after each other
     pub layers: Vec<Layer>,
     //  it is convenient to hold the input size
     pub n_inputs: usize,
+    //  return the index of the maximal output only
+    #[serde(default)]
+    pub argmax: bool,
 }
 
 impl NeuralNetwork {
 
+    /// Load Neural Network from a JSON or YAML file (by extension)
+    pub fn from_file(filename: &str) -> Result<NeuralNetwork, Box<dyn Error>> {
+        if Path::new(filename).is_file() {
+            println!("(i) Loading Neural Network from '{}' ...", filename);
+
+            let nn_str = fs::read_to_string(filename)?;
+
+            match NnFormat::from_filename(filename)? {
+                NnFormat::Json => Self::from_json_str(&nn_str),
+                NnFormat::Yaml => Self::from_yaml_str(&nn_str),
+            }
+        } else {
+            Err(format!("Neural Network file '{}' does not exist.", filename).into())
+        }
+    }
+
+    /// Save Neural Network to a JSON or YAML file (by extension)
+    pub fn save_to_file(&self, filename: &str) -> Result<(), Box<dyn Error>> {
+        let nn_str = match NnFormat::from_filename(filename)? {
+            NnFormat::Json => self.to_json_string()?,
+            NnFormat::Yaml => self.to_yaml_string()?,
+        };
+
+        let mut nn_file = File::create(filename)?;
+        nn_file.write_all(nn_str.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Load (and validate) Neural Network from a JSON string
+    pub fn from_json_str(json_str: &str) -> Result<NeuralNetwork, Box<dyn Error>> {
+        let nn: NeuralNetwork = serde_json::from_str(json_str)?;
+        nn.validate()?;
+        Ok(nn)
+    }
+
+    /// Load (and validate) Neural Network from a YAML string
+    pub fn from_yaml_str(yaml_str: &str) -> Result<NeuralNetwork, Box<dyn Error>> {
+        let nn: NeuralNetwork = serde_yaml::from_str(yaml_str)?;
+        nn.validate()?;
+        Ok(nn)
+    }
+
+    /// Serialize Neural Network into a JSON string
+    pub fn to_json_string(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Serialize Neural Network into a YAML string
+    pub fn to_yaml_string(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Check the structure of Neural Network
+    /// * there is at least one input and one layer, no layer is empty
+    /// * the number of weights of each perceptron matches the width of the preceding layer (or the input size)
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.n_inputs == 0 {return Err("Neural Network has no inputs.".into());}
+        if self.layers.is_empty() {return Err("Neural Network has no layers.".into());}
+
+        let mut width = self.n_inputs;
+        for (li, layer) in self.layers.iter().enumerate() {
+            let out_width = layer.out_len(width).map_err(|e| format!("Layer {} of Neural Network: {}", li, e))?;
+            if let Layer::Pool1d {op: PoolOp::Avg, size, ..} | Layer::Pool2d {op: PoolOp::Avg, size, ..} = layer {
+                let area = if let Layer::Pool2d {..} = layer {size * size} else {*size};
+                if !area.is_power_of_two() {
+                    return Err(format!("Average pooling in layer {} over {} values (must be a power of two).", li, area).into());
+                }
+            }
+
+            // expected number of weights of each perceptron
+            let (percs, w_len) = match layer {
+                Layer::Dense {perceptrons} => (perceptrons, width),
+                Layer::Conv1d {in_channels, kernel_size, kernels, ..} => (kernels, in_channels * kernel_size),
+                Layer::Conv2d {in_channels, kernel_size, kernels, ..} => (kernels, in_channels * kernel_size * kernel_size),
+                _ => {width = out_width; continue;},
+            };
+            if percs.is_empty() {return Err(format!("Layer {} of Neural Network is empty.", li).into());}
+            for (pi, perc) in percs.iter().enumerate() {
+                if perc.w.len() != w_len {
+                    return Err(format!("Perceptron {} in layer {} has {} weights, expected {}.", pi, li, perc.w.len(), w_len).into());
+                }
+                perc.a.validate().map_err(|e| format!("Perceptron {} in layer {}: {}", pi, li, e))?;
+            }
+            width = out_width;
+        }
+
+        Ok(())
+    }
+
     /// Evaluate Neural Network
-    pub fn eval<T: Clone + ParmArithmetics>( // T is either i32, or ParmCiphertext
+    pub fn eval<T: Clone + ParmArithmetics + Send + Sync>( // T is either i32, or ParmCiphertext
         &self,
         pc: &ParmesanCloudovo,
         inputs: &Vec<T>,
@@ -113,11 +447,84 @@ impl NeuralNetwork {
             ]
         );
 
-        ol
+        self.finish(pc, ol)
+    }
+
+    /// Output of Neural Network from the outputs of its last layer: the index of the maximum if `argmax` is set
+    pub fn finish<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        ol: Vec<T>,
+    ) -> Vec<T> {
+        if self.argmax {
+            vec![ParmArithmetics::argmax(pc, &ol)]
+        } else {
+            ol
+        }
+    }
+
+    /// Evaluate Neural Network over a batch of input vectors
+    /// * the batch is split into chunks of `chunk_size` inputs (`0` for the whole batch), each layer is evaluated
+    ///   over a whole chunk at once (i.e., bootstraps of all its inputs are scheduled together)
+    /// * outputs are passed to `on_result` together with the index of their input vector as soon as they are ready,
+    ///   n.b., not necessarily in order
+    pub fn eval_batch<T, F>(
+        &self,
+        pc: &ParmesanCloudovo,
+        inputs: &[Vec<T>],
+        chunk_size: usize,
+        on_result: F,
+    )
+    where
+        T: Clone + ParmArithmetics + Send + Sync,
+        F: Fn(usize, Vec<T>) + Sync,
+    {
+        let (last_layer, layers) = match self.layers.split_last() {
+            Some(ll) => ll,
+            None => return,
+        };
+        let chunk_size = if chunk_size == 0 {inputs.len().max(1)} else {chunk_size};
+
+        let eval_one = |layer: &Layer, il: &Vec<T>| -> Vec<T> {
+            let mut ol = Vec::new();
+            self.eval_layer::<T>(pc, layer, il, &mut ol);
+            ol
+        };
+
+        measure_duration!(
+            ["Batched Neural Network evaluation over {} ({} inputs, chunks of {})", std::any::type_name::<T>(), inputs.len(), chunk_size],
+            [
+                for (ci, chunk) in inputs.chunks(chunk_size).enumerate() {
+                    let mut ils = chunk.to_vec();
+
+                    for layer in layers.iter() {
+                        // parallel iterators
+                        #[cfg(not(feature = "seq_analyze"))]
+                        let batch_iter = ils.par_iter();
+                        // sequential iterators
+                        #[cfg(feature = "seq_analyze")]
+                        let batch_iter = ils.iter();
+
+                        ils = batch_iter.map(|il| eval_one(layer, il)).collect();
+                    }
+
+                    // last layer: results are streamed out one by one
+                    // parallel iterators
+                    #[cfg(not(feature = "seq_analyze"))]
+                    let batch_iter = ils.par_iter().enumerate();
+                    // sequential iterators
+                    #[cfg(feature = "seq_analyze")]
+                    let batch_iter = ils.iter().enumerate();
+
+                    batch_iter.for_each(|(bi, il)| on_result(ci * chunk_size + bi, self.finish(pc, eval_one(last_layer, il))));
+                }
+            ]
+        );
     }
 
     /// Evaluate a layer of NN
-    pub fn eval_layer<T: Clone + ParmArithmetics>(
+    /// * perceptrons (and positions of kernels & pooling windows) are evaluated concurrently, the output order is kept
+    pub fn eval_layer<T: Clone + ParmArithmetics + Send + Sync>(
         &self,
         pc: &ParmesanCloudovo,
         layer: &Layer,
@@ -128,87 +535,241 @@ impl NeuralNetwork {
         //DBG
         println!("\n>   LAYER   ====================================================================");
 
-        // evaluate perceptron by type
-        for (_ip, perc) in layer.iter().enumerate() {
-            measure_duration!(
-                ["{}. perceptron evaluation", _ip],
-                [
-                    match &perc.t {
-                        PercType::MAX => {
-                            let max = self.max_pool::<T>(pc, &perc.w, input, perc.b);
-                            output.push(max);
-                        },
-                        PercType::LIN => {
-                            let aff = self.affine_pool::<T>(pc, &perc.w, input, perc.b);
-                            output.push(aff);
-                        },
-                        PercType::ACT => {
-                            let aff = self.affine_pool::<T>(pc, &perc.w, input, perc.b);
-                            //DBG
-                            println!("\n>   ReLU   ---------------------------------------------------------------------");
-                            output.push(self.act_fn::<T>(pc, &aff));
-                        },
-                    }
-                ]
-            );
+        match layer {
+            Layer::Dense {perceptrons} => {
+                self.eval_dense::<T>(pc, perceptrons, input, output);
+            },
+            Layer::Rescale {pos} => {
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let in_iter = input.par_iter();
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let in_iter = input.iter();
+
+                *output = in_iter.map(|ai| ParmArithmetics::rescale(pc, ai, *pos)).collect();
+            },
+            Layer::Conv1d {kernels, ..} | Layer::Conv2d {kernels, ..} => {
+                let g = layer.grid(input.len()).expect("Layer::grid failed.").expect("Convolution without geometry.");
+                self.eval_conv::<T>(pc, &g, kernels, input, output);
+            },
+            Layer::Pool1d {op, ..} | Layer::Pool2d {op, ..} => {
+                let g = layer.grid(input.len()).expect("Layer::grid failed.").expect("Pooling without geometry.");
+                self.eval_pool::<T>(pc, &g, *op, input, output);
+            },
+            Layer::Flatten => {
+                output.extend_from_slice(input);
+            },
         }
     }
 
-    pub fn affine_pool<T: Clone + ParmArithmetics>(
+    /// Evaluate a dense layer of NN
+    fn eval_dense<T: Clone + ParmArithmetics + Send + Sync>(
         &self,
         pc: &ParmesanCloudovo,
-        w: &Vec<i32>,
-        a: &Vec<T>,
-        b: i64,
-    ) -> T {
+        layer: &[Perceptron],
+        input: &[T],
+        output: &mut Vec<T>,
+    ) {
+        // weighted inputs: all weights of an input at once (shares window multiples across perceptrons)
+        measure_duration!(
+            ["Weighted inputs ({} inputs × {} perceptrons)", input.len(), layer.len()],
+            [
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let in_iter = input.par_iter().enumerate();
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let in_iter = input.iter().enumerate();
 
-        let mut res: T = ParmArithmetics::zero();
-        let mut agg: T;
-        let mut scm: T;
-        //DBG
-        println!("\n>   AFFINE POOL   --------------------------------------------------------------");
+                let wx: Vec<Vec<T>> = in_iter.map(|(j, aj)| {
+                    // n.b., missing weights count as zero (inputs of unvalidated network may not match)
+                    let wj: Vec<i64> = layer.iter().map(|perc| perc.w.get(j).copied().unwrap_or(0) as i64).collect();
+                    ParmArithmetics::multi_scalar_mul(pc, &wj, aj)
+                }).collect();
+            ]
+        );
+
+        // transpose: weighted inputs of each perceptron (as many as its weights, cf. maximum)
+        let percs_wa: Vec<(&Perceptron, Vec<T>)> = layer.iter().enumerate().map(|(p, perc)| {
+            (perc, wx.iter().take(perc.w.len()).map(|wx_j| wx_j[p].clone()).collect())
+        }).collect();
+
+        self.eval_perceptrons::<T>(pc, &percs_wa, output);
+    }
+
+    /// Evaluate a convolution layer of NN
+    fn eval_conv<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        g: &Grid,
+        kernels: &[Perceptron],
+        input: &[T],
+        output: &mut Vec<T>,
+    ) {
+        let kk = g.kh * g.kw;
 
-        // dot product
-        for (wi, ai) in w.iter().zip(a.iter()) {
-            //DBG
-            println!(">>> scalar mul: {} ...", *wi);
-            scm = ParmArithmetics::scalar_mul(pc, *wi, ai);
-            println!(">>> addition ...");
-            agg = ParmArithmetics::add(pc, &res, &scm);
-            //WISH try directly to res, or implement add_inplace? (rather not..)
-            res = agg.clone();
+        // weighted inputs: all kernel weights that meet an input at once (shares window multiples across kernels & positions)
+        // n.b., weights at offsets that never meet the input (due to stride/borders) are skipped
+        measure_duration!(
+            ["Weighted inputs ({} inputs × {} kernels of {} weights)", input.len(), kernels.len(), g.c * kk],
+            [
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let in_iter = input.par_iter().enumerate();
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let in_iter = input.iter().enumerate();
+
+                let wx: Vec<Vec<T>> = in_iter.map(|(i, ai)| {
+                    let (c, y, x) = (i / (g.h * g.w), (i / g.w) % g.h, i % g.w);
+                    let ks: Vec<i64> = kernels.iter().flat_map(|kern| (0..kk).map(move |j| {
+                        if g.is_used(y, x, j / g.kw, j % g.kw) {kern.w[c * kk + j] as i64} else {0}
+                    })).collect();
+                    ParmArithmetics::multi_scalar_mul(pc, &ks, ai)
+                }).collect();
+            ]
+        );
+
+        // kernels at all positions (n.b., padding contributes zeros)
+        let mut percs_wa: Vec<(&Perceptron, Vec<T>)> = Vec::with_capacity(kernels.len() * g.ho * g.wo);
+        for (o, kern) in kernels.iter().enumerate() {
+            for oy in 0..g.ho {
+                for ox in 0..g.wo {
+                    let mut wa_p: Vec<T> = Vec::with_capacity(g.c * kk);
+                    for c in 0..g.c {
+                        for j in 0..kk {
+                            wa_p.push(match g.in_idx(c, oy, ox, j / g.kw, j % g.kw) {
+                                Some(i) => wx[i][o * kk + j].clone(),
+                                None    => ParmArithmetics::zero(),
+                            });
+                        }
+                    }
+                    percs_wa.push((kern, wa_p));
+                }
+            }
         }
 
-        //DBG
-        println!(">>> add bias {} ...", b);
-        // + bias
-        ParmArithmetics::add_const(pc, &res, b)
+        self.eval_perceptrons::<T>(pc, &percs_wa, output);
     }
 
-    pub fn max_pool<T: Clone + ParmArithmetics>(
+    /// Evaluate a pooling layer of NN
+    fn eval_pool<T: Clone + ParmArithmetics + Send + Sync>(
         &self,
         pc: &ParmesanCloudovo,
-        w: &Vec<i32>,
-        a: &Vec<T>,
-        b: i64,
-    ) -> T {
+        g: &Grid,
+        op: PoolOp,
+        input: &[T],
+        output: &mut Vec<T>,
+    ) {
+        let kk = g.kh * g.kw;
 
-        let mut wa: Vec<T> = Vec::new();
+        // windows of all channels & positions
+        let mut wins: Vec<Vec<T>> = Vec::with_capacity(g.c * g.ho * g.wo);
+        for c in 0..g.c {
+            for oy in 0..g.ho {
+                for ox in 0..g.wo {
+                    wins.push((0..kk).filter_map(|j| g.in_idx(c, oy, ox, j / g.kw, j % g.kw)).map(|i| input[i].clone()).collect());
+                }
+            }
+        }
+
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let win_iter = wins.par_iter();
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let win_iter = wins.iter();
+
+        *output = win_iter.map(|win| match op {
+            PoolOp::Sum => ParmArithmetics::sum(pc, win),
+            // n.b., area is a power of two (checked by validate)
+            PoolOp::Avg => ParmArithmetics::rescale(pc, &ParmArithmetics::sum(pc, win), kk.trailing_zeros() as usize),
+            PoolOp::Max => Self::max_pool_recursion::<T>(pc, win),
+        }).collect();
+    }
+
+    /// Evaluate perceptrons over (already) weighted inputs concurrently
+    /// * activation functions are applied in a separate phase, i.e., bootstraps of the whole layer run together
+    ///   (instead of waiting for the slowest perceptron's sum)
+    fn eval_perceptrons<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        percs_wa: &[(&Perceptron, Vec<T>)],
+        output: &mut Vec<T>,
+    ) {
+        // affine part (or maximum)
+        measure_duration!(
+            ["Perceptrons ({} concurrently)", percs_wa.len()],
+            [
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let pw_iter = percs_wa.par_iter();
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let pw_iter = percs_wa.iter();
 
-        // apply weights
-        for (wi, ai) in w.iter().zip(a.iter()) {
-            wa.push(ParmArithmetics::scalar_mul(pc, *wi, ai));
+                let aff: Vec<T> = pw_iter.map(|(perc, wa_p)| self.eval_perceptron::<T>(pc, perc, wa_p)).collect();
+            ]
+        );
+
+        // activation functions of ACT perceptrons
+        let n_act = percs_wa.iter().filter(|(perc, _)| perc.t == PercType::ACT).count();
+        if n_act == 0 {
+            *output = aff;
+            return;
         }
+        //DBG
+        println!("\n>   ACTIVATION   ---------------------------------------------------------------");
+        measure_duration!(
+            ["Activation functions ({} concurrently)", n_act],
+            [
+                // parallel iterators
+                #[cfg(not(feature = "seq_analyze"))]
+                let pw_aff_iter = percs_wa.par_iter().zip(aff.into_par_iter());
+                // sequential iterators
+                #[cfg(feature = "seq_analyze")]
+                let pw_aff_iter = percs_wa.iter().zip(aff.into_iter());
 
-        // locate maximum
-        let res = self.max_pool_recursion::<T>(pc, &wa);
+                *output = pw_aff_iter.map(|((perc, _), aff_p)| match perc.t {
+                    PercType::ACT => self.act_fn::<T>(pc, &perc.a, &aff_p),
+                    _ => aff_p,
+                }).collect();
+            ]
+        );
+    }
 
-        // + bias
-        ParmArithmetics::add_const(pc, &res, b)
+    /// Evaluate perceptron by type over (already) weighted inputs, without activation function
+    fn eval_perceptron<T: Clone + ParmArithmetics + Send + Sync>(
+        &self,
+        pc: &ParmesanCloudovo,
+        perc: &Perceptron,
+        wa_p: &Vec<T>,
+    ) -> T {
+        match &perc.t {
+            PercType::MAX => {
+                let max = Self::max_pool_recursion::<T>(pc, wa_p);
+                ParmArithmetics::add_const(pc, &max, perc.b)
+            },
+            PercType::LIN | PercType::ACT => {
+                self.weighted_sum::<T>(pc, wa_p, perc.b)
+            },
+        }
     }
 
-    fn max_pool_recursion<T: Clone + ParmArithmetics>(
+    /// Sum of (already) weighted inputs + bias
+    fn weighted_sum<T: Clone + ParmArithmetics>(
         &self,
+        pc: &ParmesanCloudovo,
+        wa: &[T],
+        b: i64,
+    ) -> T {
+        let mut rows = wa.to_vec();
+        rows.push(ParmArithmetics::add_const(pc, &ParmArithmetics::zero(), b));
+        ParmArithmetics::sum(pc, &rows)
+    }
+
+    pub(crate) fn max_pool_recursion<T: Clone + ParmArithmetics + Send + Sync>(
         pc: &ParmesanCloudovo,
         a: &Vec<T>,
     ) -> T {
@@ -219,24 +780,48 @@ impl NeuralNetwork {
             return a[0].clone();
         }
 
-        let mut a_half: Vec<T> = Vec::new();
-        for aic in a.chunks(2) {
+        // pairs are independent
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let a_pairs_iter = a.par_chunks(2);
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let a_pairs_iter = a.chunks(2);
+
+        let a_half: Vec<T> = a_pairs_iter.map(|aic| {
             if aic.len() == 2 {
-                a_half.push(ParmArithmetics::max(pc, &aic[0], &aic[1]));
+                ParmArithmetics::max(pc, &aic[0], &aic[1])
             } else {
-                a_half.push(aic[0].clone())
+                aic[0].clone()
             }
-        }
+        }).collect();
 
-        return self.max_pool_recursion::<T>(pc, &a_half);
+        return Self::max_pool_recursion::<T>(pc, &a_half);
     }
 
     pub fn act_fn<T: ParmArithmetics>(
         &self,
         pc: &ParmesanCloudovo,
+        a: &Activation,
         lc: &T,   // lc .. for linear combination
     ) -> T {
-        ParmArithmetics::relu(pc, lc)    // sgn   relu
+        a.eval(pc, lc)
+    }
+}
+
+/// File format of Neural Network
+enum NnFormat {
+    Json,
+    Yaml,
+}
+
+impl NnFormat {
+    fn from_filename(filename: &str) -> Result<NnFormat, Box<dyn Error>> {
+        match Path::new(filename).extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(NnFormat::Json),
+            Some("yaml") | Some("yml") => Ok(NnFormat::Yaml),
+            _ => Err(format!("Unknown Neural Network file format of '{}' (expected .json, .yaml or .yml).", filename).into()),
+        }
     }
 }
 
@@ -251,3 +836,17 @@ impl Distribution<PercType> for Standard {
         }
     }
 }
+
+/// Generate random `Activation`
+impl Distribution<Activation> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Activation {
+        match rng.gen_range(0..=5) {
+            0 => Activation::Sign,
+            1 => Activation::Relu,
+            2 => Activation::LeakyRelu {shift: rng.gen_range(1..=3)},
+            3 => Activation::HardTanh {frac: rng.gen_range(0..=3)},
+            4 => Activation::HardSigmoid {frac: rng.gen_range(1..=3)},
+            _ => Activation::Identity,
+        }
+    }
+}
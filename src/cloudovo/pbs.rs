@@ -295,6 +295,22 @@ pub fn a_1__pi_5(
     )
 }
 
+//
+//  |X| ≥ 1 (× val)
+//
+#[allow(non_snake_case)]
+pub fn a_1__pi_5__with_val(
+    pc: &ParmesanCloudovo,
+    c: &ParmEncrWord,
+    val: u64,
+) -> ParmEncrWord {
+    eval_LUT_5_uint(
+        pc,
+        c,
+        [0,val,val,val,val,val,val,val,32-val,32-val,32-val,32-val,32-val,32-val,32-val,32-val]
+    )
+}
+
 //
 //  3-bit squaring (usable for 2-bit squ, too)
 //
@@ -0,0 +1,186 @@
+use std::error::Error;
+
+use crate::*;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+#[cfg(not(feature = "seq_analyze"))]
+use crossbeam_utils::thread;
+// fake threads for sequential analysis
+#[cfg(feature = "seq_analyze")]
+use crate::seq_utils::thread;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
+
+/// Maximal length of an operand of squaring (cf. `squ_impl`, `mul_impl`)
+const SQU_MAXLEN: usize = 32;
+
+
+// =============================================================================
+//
+//  Exponentiation
+//
+
+/// Implementation of exponentiation by a public exponent: `X^e`
+/// * binary addition chain: squares `X^(2^i)` are evaluated one after each other (by `squ_impl`),
+///   the ones of non-zero bits of `e` are multiplied by a balanced tree of products (in parallel)
+pub fn pow_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    e:  u32,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    // resolve e ≤ 1
+    if e == 0 {return Ok(ParmArithmetics::add_const(pc, &ParmCiphertext::empty(), 1));}
+    if e == 1 {return Ok(x.clone());}
+    check_squ_len(x.len(), e as usize, 0)?;
+
+    let res;
+
+    measure_duration!(
+        ["Exponentiation ({}-bit ^ {})", x.len(), e],
+        [
+            // X^(2^i) for all bits of e
+            let mut sq = x.clone();
+            let mut factors: Vec<ParmCiphertext> = Vec::new();
+            for i in 0..encryption::bit_len_32(e) {
+                if i > 0 {sq = ParmArithmetics::squ(pc, &sq);}
+                if (e >> i) & 1 == 1 {factors.push(sq.clone());}
+            }
+
+            // product tree
+            res = prod_tree(pc, factors);
+        ]
+    );
+
+    Ok(res)
+}
+
+/// Product of (non-empty) vector of ciphertexts by a balanced tree (in parallel)
+/// * products go via mul-arrays of `inner_prod`: Karatsuba's scoped threads must not be spawned from within the rayon pool
+fn prod_tree(
+    pc: &ParmesanCloudovo,
+    mut factors: Vec<ParmCiphertext>,
+) -> ParmCiphertext {
+    while factors.len() > 1 {
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let f_iter = factors.par_chunks(2);
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let f_iter = factors.chunks(2);
+
+        factors = f_iter.map(|fc| {
+            if fc.len() == 2 {ParmArithmetics::inner_prod(pc, &fc[0..1], &fc[1..2])} else {fc[0].clone()}
+        }).collect();
+    }
+    factors.pop().unwrap_or_default()
+}
+
+/// Check (up front) that all squarings of powers `X^(2^j)` up to `X^deg` fit into `SQU_MAXLEN` words
+/// * the square of `n` words has at most `2n + 4` words, `frac` words are dropped by rescaling (fixed-point numbers)
+/// * products go via mul-arrays of `inner_prod`, which are not limited in length
+pub(crate) fn check_squ_len(
+    x_len: usize,
+    deg: usize,
+    frac: usize,
+) -> Result<(), Box<dyn Error>> {
+    let mut len = x_len;
+    let mut p = 1usize;
+    while p <= deg / 2 {
+        if len > SQU_MAXLEN {
+            return Err(format!("Power X^{} of {}-word X exceeds the squaring limit of {} words.", 2 * p, x_len, SQU_MAXLEN).into());
+        }
+        len = (2 * len + 4 + if frac > 0 {1} else {0}).saturating_sub(frac);
+        p *= 2;
+    }
+    Ok(())
+}
+
+
+// =============================================================================
+//
+//  Polynomial Evaluation
+//
+
+/// Implementation of polynomial evaluation with plaintext coefficients: `Σ c_i·X^i`
+/// * Estrin's scheme: pairs of terms `c_2i + c_(2i+1)·X` (by scalar multiplication), then
+///   pairs of pairs `p_2i + p_(2i+1)·X^2`, ... , each level in parallel
+/// * the multiplicative depth is `⌈log_2 (deg + 1)⌉`, powers `X^(2^j)` are evaluated alongside
+/// * products within a level go via mul-arrays of `inner_prod` (cf. `prod_tree`)
+pub fn poly_eval_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    coeffs: &[i64],
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    // trim leading zero coefficients
+    let deg_p1 = coeffs.iter().rposition(|c| *c != 0).map_or(0, |d| d + 1);
+    if deg_p1 == 0 {return Ok(ParmArithmetics::zero());}
+    let coeffs = &coeffs[0..deg_p1];
+    check_squ_len(x.len(), deg_p1 - 1, 0)?;
+
+    let mut terms: Vec<ParmCiphertext>;
+
+    measure_duration!(
+        ["Polynomial evaluation (degree {}, {}-bit)", deg_p1 - 1, x.len()],
+        [
+            // level 0: c_2i + c_(2i+1)·X
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let c_iter = coeffs.par_chunks(2);
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let c_iter = coeffs.chunks(2);
+
+            terms = c_iter.map(|cc| {
+                let c1_x = if cc.len() == 2 {ParmArithmetics::scalar_mul(pc, cc[1], x)} else {ParmArithmetics::zero()};
+                ParmArithmetics::add_const(pc, &c1_x, cc[0])
+            }).collect();
+
+            // next levels: p_2i + p_(2i+1)·X^(2^j)
+            let mut xp = if terms.len() > 1 {ParmArithmetics::squ(pc, x)} else {ParmCiphertext::empty()};
+            while terms.len() > 1 {
+                let mut new_terms: Vec<ParmCiphertext> = Vec::new();
+                let mut new_xp = ParmCiphertext::empty();
+
+                let ntr = &mut new_terms;
+                let nxr = &mut new_xp;
+                let tr = &terms;
+                let xpr = &xp;
+
+                // parallel pool: terms of next level, next power (only if needed)
+                thread::scope(|tx_scope| {
+                    tx_scope.spawn(|_| {
+                        // parallel iterators
+                        #[cfg(not(feature = "seq_analyze"))]
+                        let t_iter = tr.par_chunks(2);
+                        // sequential iterators
+                        #[cfg(feature = "seq_analyze")]
+                        let t_iter = tr.chunks(2);
+
+                        *ntr = t_iter.map(|tc| {
+                            if tc.len() == 2 {
+                                ParmArithmetics::add(pc, &tc[0], &ParmArithmetics::inner_prod(pc, &tc[1..2], std::slice::from_ref(xpr)))
+                            } else {
+                                tc[0].clone()
+                            }
+                        }).collect();
+                    });
+                    if tr.len() > 2 {
+                        tx_scope.spawn(|_| {
+                            *nxr = ParmArithmetics::squ(pc, xpr);
+                        });
+                    }
+                }).expect("thread::scope tx_scope failed.");
+
+                terms = new_terms;
+                xp = new_xp;
+            }
+        ]
+    );
+
+    Ok(terms.pop().unwrap_or_default())
+}
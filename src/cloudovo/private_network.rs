@@ -0,0 +1,275 @@
+//!
+//! # Module for Neural Network evaluation with encrypted weights (private model)
+//!
+//! Weights & biases are supplied (encrypted) by the model owner, only the structure
+//! (perceptron types, activation functions, geometry of layers) is public:
+//! * dense layers use encrypted-by-encrypted products (multiplication arrays of `inner_prod`) instead of `scalar_mul`,
+//! * layers without parameters (rescaling, pooling, flatten) are evaluated as in `NeuralNetwork`.
+//!
+//! Since products of two ciphertexts are as long as both factors together, the outputs of each
+//! layer are trimmed to their worst-case bounds, which follow from the public bit-lengths of
+//! inputs & parameters (cf. `LengthPlan`).
+//!
+//! n.b., convolution layers are not supported.
+
+use std::error::Error;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+
+use crate::*;
+use crate::arithmetics::ParmArithmetics;
+use super::neural_network::{Perceptron, PercType, Activation, Layer, NeuralNetwork};
+use super::length_plan::{self, Bounds};
+
+/// Perceptron with private weights & bias
+#[derive(Debug, Clone)]
+pub struct PrivatePerceptron<T> {
+    // perceptron type
+    pub t: PercType,
+    // weights to perceptrons in the preceeding layer
+    pub w: Vec<T>,
+    // bias
+    pub b: T,
+    // activation function (applies to ACT only)
+    pub a: Activation,
+}
+
+/// Layer of private Neural Network
+#[derive(Debug, Clone)]
+pub enum PrivateLayer<T> {
+    // dense layer with private weights & biases
+    Dense {perceptrons: Vec<PrivatePerceptron<T>>},
+    // layer without parameters (rescale, pooling, flatten)
+    Public(Layer),
+}
+
+/// Neural Network with private weights & biases
+#[derive(Debug, Clone)]
+pub struct PrivateNetwork<T> {
+    pub layers: Vec<PrivateLayer<T>>,
+    pub n_inputs: usize,
+    // public bound on weights & biases: |w|, |b| < 2^param_bits
+    pub param_bits: usize,
+    // return the index of the maximal output only (cf. `NeuralNetwork`)
+    pub argmax: bool,
+}
+
+impl<T: Clone + ParmArithmetics + Send + Sync> PrivateNetwork<T> {
+
+    /// Private Neural Network from a plain one, weights & biases (`|v| < 2^param_bits`) are converted by `f` (e.g., encrypted)
+    pub fn from_nn<F>(
+        nn: &NeuralNetwork,
+        param_bits: usize,
+        f: F,
+    ) -> Result<PrivateNetwork<T>, Box<dyn Error>>
+    where F: Fn(i64) -> Result<T, Box<dyn Error>> {
+        nn.validate()?;
+        if param_bits == 0 || param_bits > 62 {
+            return Err(format!("Parameter bit-length {} out of range (1 ..= 62).", param_bits).into());
+        }
+
+        let conv_param = |v: i64| -> Result<T, Box<dyn Error>> {
+            if v.unsigned_abs() >= 1 << param_bits {
+                return Err(format!("Parameter {} does not fit in {} bits.", v, param_bits).into());
+            }
+            f(v)
+        };
+        let conv_perc = |perc: &Perceptron| -> Result<PrivatePerceptron<T>, Box<dyn Error>> {
+            Ok(PrivatePerceptron {
+                t: perc.t,
+                w: perc.w.iter().map(|wi| conv_param(*wi as i64)).collect::<Result<Vec<T>, Box<dyn Error>>>()?,
+                b: conv_param(perc.b)?,
+                a: perc.a,
+            })
+        };
+
+        let layers = nn.layers.iter().enumerate().map(|(li, layer)| match layer {
+            Layer::Dense {perceptrons} => Ok(PrivateLayer::Dense {
+                perceptrons: perceptrons.iter().map(conv_perc).collect::<Result<Vec<PrivatePerceptron<T>>, Box<dyn Error>>>()
+                    .map_err(|e| format!("Layer {}: {}", li, e))?,
+            }),
+            Layer::Conv1d {..} | Layer::Conv2d {..} => Err(format!("Convolution in layer {} not supported with private weights.", li).into()),
+            _ => Ok(PrivateLayer::Public(layer.clone())),
+        }).collect::<Result<Vec<PrivateLayer<T>>, Box<dyn Error>>>()?;
+
+        Ok(PrivateNetwork {layers, n_inputs: nn.n_inputs, param_bits, argmax: nn.argmax})
+    }
+
+    /// Check the structure of private Neural Network (cf. `NeuralNetwork::validate`)
+    /// * public layers must not hold any weights
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.n_inputs == 0 {return Err("Neural Network has no inputs.".into());}
+        if self.layers.is_empty() {return Err("Neural Network has no layers.".into());}
+        if self.param_bits == 0 || self.param_bits > 62 {
+            return Err(format!("Parameter bit-length {} out of range (1 ..= 62).", self.param_bits).into());
+        }
+
+        let mut width = self.n_inputs;
+        for (li, layer) in self.layers.iter().enumerate() {
+            match layer {
+                PrivateLayer::Dense {perceptrons} => {
+                    if perceptrons.is_empty() {return Err(format!("Layer {} of Neural Network is empty.", li).into());}
+                    for (pi, perc) in perceptrons.iter().enumerate() {
+                        if perc.w.len() != width {
+                            return Err(format!("Perceptron {} in layer {} has {} weights, expected {}.", pi, li, perc.w.len(), width).into());
+                        }
+                        perc.a.validate().map_err(|e| format!("Perceptron {} in layer {}: {}", pi, li, e))?;
+                    }
+                    width = perceptrons.len();
+                },
+                PrivateLayer::Public(Layer::Dense {..} | Layer::Conv1d {..} | Layer::Conv2d {..}) => {
+                    return Err(format!("Public layer {} holds weights.", li).into());
+                },
+                PrivateLayer::Public(l) => {
+                    NeuralNetwork {layers: vec![l.clone()], n_inputs: width, argmax: false}.validate()
+                        .map_err(|e| format!("Layer {}: {}", li, e))?;
+                    width = l.out_len(width)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate private Neural Network on inputs `|x| < 2^in_bits` (e.g., their number of words)
+    /// * after each layer, outputs are trimmed to their worst-case bit-lengths (checked before evaluation, cf. `layer_bits`)
+    pub fn eval(
+        &self,
+        pc: &ParmesanCloudovo,
+        inputs: &[T],
+        in_bits: usize,
+    ) -> Result<Vec<T>, Box<dyn Error>> {
+        if inputs.len() != self.n_inputs {
+            return Err(format!("Neural Network expects {} inputs, got {}.", self.n_inputs, inputs.len()).into());
+        }
+        let layer_bits = self.layer_bits(in_bits)?;
+        let mut il = length_plan::trim_all(pc, inputs, &vec![in_bits; inputs.len()]);
+
+        measure_duration!(
+            ["Private Neural Network evaluation over {}", std::any::type_name::<T>()],
+            [
+                for (layer, out_bits) in self.layers.iter().zip(layer_bits.iter()) {
+                    let ol = match layer {
+                        PrivateLayer::Dense {perceptrons} => Self::eval_dense(pc, perceptrons, &il),
+                        // n.b., single-layer network
+                        PrivateLayer::Public(l) => NeuralNetwork {layers: vec![l.clone()], n_inputs: il.len(), argmax: false}.eval(pc, &il),
+                    };
+                    il = length_plan::trim_all(pc, &ol, out_bits);
+                }
+            ]
+        );
+
+        Ok(if self.argmax {
+            vec![ParmArithmetics::argmax(pc, &il)]
+        } else {
+            il
+        })
+    }
+
+    /// Worst-case bit-lengths of the outputs of each layer for inputs `|x| < 2^in_bits`
+    /// * accumulated values as well as outputs must fit in 62 bits (cf. `LengthPlan::new`)
+    fn layer_bits(
+        &self,
+        in_bits: usize,
+    ) -> Result<Vec<Vec<usize>>, Box<dyn Error>> {
+        if in_bits > 62 {
+            return Err(format!("Input bit-length {} out of range (0 ..= 62).", in_bits).into());
+        }
+
+        let in_max = (1i128 << in_bits) - 1;
+        let mut bounds: Vec<Bounds> = vec![(-in_max, in_max); self.n_inputs];
+        let mut layer_bits = Vec::new();
+
+        for (li, layer) in self.layers.iter().enumerate() {
+            let err_li = |e: Box<dyn Error>| -> Box<dyn Error> {format!("Layer {}: {}", li, e).into()};
+
+            bounds = match layer {
+                PrivateLayer::Dense {perceptrons} => self.dense_bounds(perceptrons, &bounds),
+                PrivateLayer::Public(l) => length_plan::layer_bounds(l, &bounds).map(|(_, out)| out),
+            }.map_err(err_li)?;
+            layer_bits.push(bounds.iter().map(|bi| length_plan::bits_of(&[*bi])).collect::<Result<Vec<usize>, Box<dyn Error>>>().map_err(err_li)?);
+        }
+
+        Ok(layer_bits)
+    }
+
+    /// Worst-case bounds on the outputs of a dense layer, given bounds on its inputs and `|w|, |b| < 2^param_bits`
+    fn dense_bounds(
+        &self,
+        perceptrons: &[PrivatePerceptron<T>],
+        in_bounds: &[Bounds],
+    ) -> Result<Vec<Bounds>, Box<dyn Error>> {
+        let p = (1i128 << self.param_bits) - 1;
+        let overflow = || -> Box<dyn Error> {"Worst-case bound overflows.".into()};
+
+        let terms: Vec<Bounds> = in_bounds.iter().map(|(lo, hi)| {
+            let m = lo.abs().max(hi.abs()).checked_mul(p).ok_or_else(overflow)?;
+            Ok((-m, m))
+        }).collect::<Result<Vec<Bounds>, Box<dyn Error>>>()?;
+
+        perceptrons.iter().map(|perc| {
+            let (lo, hi) = match perc.t {
+                PercType::MAX => length_plan::max_bounds(&terms),
+                PercType::LIN | PercType::ACT => terms.iter().try_fold((0i128, 0i128), |(lo, hi), (tl, th)| {
+                    Some((lo.checked_add(*tl)?, hi.checked_add(*th)?))
+                }).ok_or_else(overflow)?,
+            };
+            let acc = (lo.checked_sub(p).ok_or_else(overflow)?, hi.checked_add(p).ok_or_else(overflow)?);
+            length_plan::bits_of(&[acc])?;
+            Ok(match perc.t {
+                PercType::ACT => (length_plan::act_bound(&perc.a, acc.0), length_plan::act_bound(&perc.a, acc.1)),
+                _ => acc,
+            })
+        }).collect()
+    }
+
+    /// Evaluate a dense layer with private weights: perceptrons concurrently, then their activation functions
+    fn eval_dense(
+        pc: &ParmesanCloudovo,
+        perceptrons: &[PrivatePerceptron<T>],
+        input: &[T],
+    ) -> Vec<T> {
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let perc_iter = perceptrons.par_iter();
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let perc_iter = perceptrons.iter();
+
+        let aff: Vec<T> = perc_iter.map(|perc| {
+            let acc = match perc.t {
+                PercType::MAX => {
+                    // parallel iterators
+                    #[cfg(not(feature = "seq_analyze"))]
+                    let w_a_iter = perc.w.par_iter().zip(input.par_iter());
+                    // sequential iterators
+                    #[cfg(feature = "seq_analyze")]
+                    let w_a_iter = perc.w.iter().zip(input.iter());
+
+                    // n.b., single-item inner products do not spawn threads (unlike Karatsuba in `mul`), which would block the rayon pool
+                    let wa: Vec<T> = w_a_iter.map(|(wi, ai)| ParmArithmetics::inner_prod(pc, std::slice::from_ref(wi), std::slice::from_ref(ai))).collect();
+                    NeuralNetwork::max_pool_recursion(pc, &wa)
+                },
+                PercType::LIN | PercType::ACT => ParmArithmetics::inner_prod(pc, &perc.w, input),
+            };
+            ParmArithmetics::add(pc, &acc, &perc.b)
+        }).collect();
+
+        // parallel iterators
+        #[cfg(not(feature = "seq_analyze"))]
+        let perc_aff_iter = perceptrons.par_iter().zip(aff.into_par_iter());
+        // sequential iterators
+        #[cfg(feature = "seq_analyze")]
+        let perc_aff_iter = perceptrons.iter().zip(aff.into_iter());
+
+        perc_aff_iter.map(|(perc, aff_p)| match perc.t {
+            PercType::ACT => perc.a.eval(pc, &aff_p),
+            _ => aff_p,
+        }).collect()
+    }
+}
@@ -0,0 +1,320 @@
+//!
+//! # Module for quantization of floating-point Neural Networks
+//!
+//! Weights of each dense layer are scaled by a power of two `2^k`, chosen so that they fit the bit budget,
+//! values between layers are kept with `frac` fractional bits, i.e., after a layer with `k > 0`,
+//! a rescaling layer (rounding by `round_at`) is inserted:
+//!
+//! ```text
+//!     x · 2^frac  -->  Dense (w · 2^k, b · 2^(frac+k))  -->  Rescale (pos = k)  -->  y · 2^frac
+//! ```
+//!
+//! Activation functions are evaluated before rescaling, i.e., over values with `frac + k` fractional bits
+//! (`sign` is not supported, since its output does not scale).
+
+use std::error::Error;
+use std::fmt;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+use serde::{Serialize, Deserialize};
+
+use crate::ParmesanCloudovo;
+use super::neural_network::{Perceptron, PercType, Activation, Layer, NeuralNetwork};
+
+/// Floating-point perceptron (as trained)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatPerceptron {
+    // perceptron type
+    pub t: PercType,
+    // weights to perceptrons in the preceeding layer
+    pub w: Vec<f64>,
+    // bias
+    pub b: f64,
+    // activation function (applies to ACT only; ReLU if omitted, fractional bits ignored)
+    #[serde(default)]
+    pub a: Activation,
+}
+
+/// Floating-point Neural Network (dense layers only)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatNetwork {
+    pub layers: Vec<Vec<FloatPerceptron>>,
+    pub n_inputs: usize,
+}
+
+impl FloatNetwork {
+
+    /// Load (and validate) floating-point Neural Network from a JSON string
+    pub fn from_json_str(json_str: &str) -> Result<FloatNetwork, Box<dyn Error>> {
+        let fnn: FloatNetwork = serde_json::from_str(json_str)?;
+        fnn.validate()?;
+        Ok(fnn)
+    }
+
+    /// Load (and validate) floating-point Neural Network from a YAML string
+    pub fn from_yaml_str(yaml_str: &str) -> Result<FloatNetwork, Box<dyn Error>> {
+        let fnn: FloatNetwork = serde_yaml::from_str(yaml_str)?;
+        fnn.validate()?;
+        Ok(fnn)
+    }
+
+    /// Check the structure of floating-point Neural Network (cf. `NeuralNetwork::validate`)
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.n_inputs == 0 {return Err("Neural Network has no inputs.".into());}
+        if self.layers.is_empty() {return Err("Neural Network has no layers.".into());}
+
+        let mut width = self.n_inputs;
+        for (li, layer) in self.layers.iter().enumerate() {
+            if layer.is_empty() {return Err(format!("Layer {} of Neural Network is empty.", li).into());}
+            for (pi, perc) in layer.iter().enumerate() {
+                if perc.w.len() != width {
+                    return Err(format!("Perceptron {} in layer {} has {} weights, expected {}.", pi, li, perc.w.len(), width).into());
+                }
+                if !perc.b.is_finite() || perc.w.iter().any(|wi| !wi.is_finite()) {
+                    return Err(format!("Perceptron {} in layer {} has a non-finite parameter.", pi, li).into());
+                }
+                perc.a.validate().map_err(|e| format!("Perceptron {} in layer {}: {}", pi, li, e))?;
+            }
+            width = layer.len();
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate floating-point Neural Network (reference for `NeuralNetwork::eval`)
+    pub fn eval(&self, inputs: &[f64]) -> Vec<f64> {
+        let mut il = inputs.to_vec();
+
+        for layer in self.layers.iter() {
+            il = layer.iter().map(|perc| {
+                let wa = perc.w.iter().zip(il.iter()).map(|(wi, ai)| wi * ai);
+                match perc.t {
+                    PercType::MAX => wa.fold(None, |m: Option<f64>, v| Some(m.map_or(v, |m| m.max(v)))).unwrap_or(0.0) + perc.b,
+                    PercType::LIN => wa.sum::<f64>() + perc.b,
+                    PercType::ACT => act_f64(&perc.a, wa.sum::<f64>() + perc.b),
+                }
+            }).collect();
+        }
+
+        il
+    }
+}
+
+/// Quantization parameters
+#[derive(Debug, Clone, Copy)]
+pub struct QuantParams {
+    // bit budget of weights (incl. sign), i.e., |w| < 2^(w_bits-1)
+    pub w_bits: usize,
+    // fractional bits of inputs & of values between layers
+    pub frac: usize,
+}
+
+/// Quantized Neural Network
+#[derive(Debug, Clone)]
+pub struct QuantizedNetwork {
+    // integer network (incl. rescaling layers)
+    pub nn: NeuralNetwork,
+    // weights of i-th dense layer are scaled by 2^w_exps[i]
+    pub w_exps: Vec<i32>,
+    // fractional bits of inputs
+    pub in_frac: usize,
+    // fractional bits of outputs (n.b., less than in_frac if weights of the last layer need a negative exponent)
+    pub out_frac: i32,
+}
+
+impl QuantizedNetwork {
+
+    /// Quantize floating-point Neural Network
+    /// * for each layer, the largest `k` such that `|w|·2^k` rounds into the bit budget is chosen
+    /// * after a layer with `k > 0`, rescaling by `2^-k` (with rounding) is inserted
+    pub fn quantize(
+        fnn: &FloatNetwork,
+        qp: QuantParams,
+    ) -> Result<QuantizedNetwork, Box<dyn Error>> {
+        if qp.w_bits < 2 || qp.w_bits > 31 {
+            return Err(format!("Weight bit budget {} out of range (2..=31).", qp.w_bits).into());
+        }
+        fnn.validate()?;
+
+        let w_max = ((1i64 << (qp.w_bits - 1)) - 1) as f64;
+        let mut layers = Vec::new();
+        let mut w_exps = Vec::new();
+        // fractional bits of the current values
+        let mut f = qp.frac as i32;
+
+        for (li, flayer) in fnn.layers.iter().enumerate() {
+            // largest weight of the layer
+            let m = flayer.iter().flat_map(|perc| perc.w.iter()).fold(0.0f64, |m, wi| m.max(wi.abs()));
+
+            // largest k s.t. round(m·2^k) ≤ w_max
+            let mut k = if m > 0.0 {(w_max / m).log2().floor() as i32} else {0};
+            while (m * 2f64.powi(k)).round() > w_max {k -= 1;}
+
+            // accumulated values have f + k fractional bits
+            let acc_f = f + k;
+            let perceptrons = flayer.iter().map(|perc| {
+                Ok(Perceptron {
+                    t: perc.t,
+                    w: perc.w.iter().map(|wi| Self::quantize_val(*wi, k).map(|wq| wq as i32)).collect::<Result<Vec<i32>, Box<dyn Error>>>()?,
+                    b: Self::quantize_val(perc.b, acc_f)?,
+                    a: Self::quantize_act(&perc.a, perc.t, acc_f)?,
+                })
+            }).collect::<Result<Vec<Perceptron>, Box<dyn Error>>>()
+                .map_err(|e| format!("Layer {}: {}", li, e))?;
+            layers.push(Layer::Dense {perceptrons});
+            w_exps.push(k);
+
+            // rescale back to frac fractional bits
+            if acc_f > qp.frac as i32 {
+                layers.push(Layer::Rescale {pos: (acc_f - qp.frac as i32) as usize});
+                f = qp.frac as i32;
+            } else {
+                f = acc_f;
+            }
+        }
+
+        let nn = NeuralNetwork {layers, n_inputs: fnn.n_inputs, argmax: false};
+        nn.validate()?;
+
+        Ok(QuantizedNetwork {
+            nn,
+            w_exps,
+            in_frac: qp.frac,
+            out_frac: f,
+        })
+    }
+
+    /// Quantize inputs: `round(x·2^in_frac)`
+    pub fn quantize_inputs(&self, inputs: &[f64]) -> Result<Vec<i64>, Box<dyn Error>> {
+        inputs.iter().map(|xi| Self::quantize_val(*xi, self.in_frac as i32)).collect()
+    }
+
+    /// Dequantize outputs: `y / 2^out_frac`
+    pub fn dequantize_outputs(&self, outputs: &[i64]) -> Vec<f64> {
+        outputs.iter().map(|yi| *yi as f64 * 2f64.powi(-self.out_frac)).collect()
+    }
+
+    /// Compare quantized network (evaluated over `i64`) with the floating-point one
+    /// * if labels are given, also the accuracies of classification (by the largest output) are reported
+    pub fn report(
+        &self,
+        pc: &ParmesanCloudovo,
+        fnn: &FloatNetwork,
+        inputs: &[Vec<f64>],
+        labels: Option<&[usize]>,
+    ) -> Result<QuantReport, Box<dyn Error>> {
+        if inputs.is_empty() {return Err("No inputs to report on.".into());}
+        if let Some(l) = labels {
+            if l.len() != inputs.len() {return Err(format!("Got {} labels for {} inputs.", l.len(), inputs.len()).into());}
+        }
+
+        let mut max_abs_err = 0.0f64;
+        let mut sum_abs_err = 0.0f64;
+        let mut n_outs = 0usize;
+        let mut n_agree = 0usize;
+        let mut n_corr_float = 0usize;
+        let mut n_corr_quant = 0usize;
+
+        for (i, x) in inputs.iter().enumerate() {
+            let y_f = fnn.eval(x);
+            let xq = self.quantize_inputs(x)?;
+            let y_q = self.dequantize_outputs(&self.nn.eval::<i64>(pc, &xq));
+
+            for (yfi, yqi) in y_f.iter().zip(y_q.iter()) {
+                let e = (yfi - yqi).abs();
+                max_abs_err = max_abs_err.max(e);
+                sum_abs_err += e;
+                n_outs += 1;
+            }
+
+            let c_f = argmax(&y_f);
+            let c_q = argmax(&y_q);
+            if c_f == c_q {n_agree += 1;}
+            if let Some(l) = labels {
+                if c_f == l[i] {n_corr_float += 1;}
+                if c_q == l[i] {n_corr_quant += 1;}
+            }
+        }
+
+        let n = inputs.len() as f64;
+        Ok(QuantReport {
+            max_abs_err,
+            mean_abs_err: sum_abs_err / n_outs as f64,
+            argmax_agreement: n_agree as f64 / n,
+            acc_float: labels.map(|_| n_corr_float as f64 / n),
+            acc_quant: labels.map(|_| n_corr_quant as f64 / n),
+        })
+    }
+
+    /// Quantize activation function over values with `f` fractional bits
+    fn quantize_act(a: &Activation, t: PercType, f: i32) -> Result<Activation, Box<dyn Error>> {
+        if t != PercType::ACT {return Ok(*a);}
+        match a {
+            Activation::Sign => Err("Sign activation cannot be quantized.".into()),
+            Activation::HardTanh {..} if f < 0 => Err(format!("Hard tanh over values with {} fractional bits not supported.", f).into()),
+            Activation::HardSigmoid {..} if f < 1 => Err(format!("Hard sigmoid over values with {} fractional bits not supported.", f).into()),
+            Activation::HardTanh {..}    => Ok(Activation::HardTanh {frac: f as usize}),
+            Activation::HardSigmoid {..} => Ok(Activation::HardSigmoid {frac: f as usize}),
+            _ => Ok(*a),
+        }
+    }
+
+    /// Quantize a value with `f` fractional bits: `round(v·2^f)`
+    fn quantize_val(v: f64, f: i32) -> Result<i64, Box<dyn Error>> {
+        let vq = (v * 2f64.powi(f)).round();
+        if !vq.is_finite() || vq.abs() >= (1u64 << 62) as f64 {
+            return Err(format!("Value {} with {} fractional bits out of range.", v, f).into());
+        }
+        Ok(vq as i64)
+    }
+}
+
+/// Report of quantization: plaintext accuracy delta
+#[derive(Debug, Clone)]
+pub struct QuantReport {
+    // largest & mean absolute error of outputs
+    pub max_abs_err: f64,
+    pub mean_abs_err: f64,
+    // ratio of inputs, where the largest output is at the same position
+    pub argmax_agreement: f64,
+    // accuracies of classification (if labels given)
+    pub acc_float: Option<f64>,
+    pub acc_quant: Option<f64>,
+}
+
+impl QuantReport {
+    /// Accuracy delta: quantized - floating-point (if labels given)
+    pub fn acc_delta(&self) -> Option<f64> {
+        Some(self.acc_quant? - self.acc_float?)
+    }
+}
+
+impl fmt::Display for QuantReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "max |err| = {:.6}, mean |err| = {:.6}, argmax agreement = {:.2} %",
+            self.max_abs_err, self.mean_abs_err, 100.0 * self.argmax_agreement)?;
+        if let (Some(af), Some(aq), Some(ad)) = (self.acc_float, self.acc_quant, self.acc_delta()) {
+            write!(f, ", accuracy {:.2} % -> {:.2} % ({:+.2} %)", 100.0 * af, 100.0 * aq, 100.0 * ad)?;
+        }
+        Ok(())
+    }
+}
+
+/// Floating-point activation function (cf. `Activation`)
+fn act_f64(a: &Activation, x: f64) -> f64 {
+    match a {
+        Activation::Sign                => if x > 0.0 {1.0} else if x < 0.0 {-1.0} else {0.0},
+        Activation::Relu                => x.max(0.0),
+        Activation::LeakyRelu {shift}   => x.max(x * 2f64.powi(-(*shift as i32))),
+        Activation::HardTanh {..}       => x.clamp(-1.0, 1.0),
+        Activation::HardSigmoid {..}    => (x / 4.0 + 0.5).clamp(0.0, 1.0),
+        Activation::Identity            => x,
+    }
+}
+
+/// Position of the (first) largest value
+fn argmax(v: &[f64]) -> usize {
+    v.iter().enumerate().fold(0, |im, (i, vi)| if *vi > v[im] {i} else {im})
+}
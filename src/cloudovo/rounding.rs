@@ -8,14 +8,111 @@ use crate::*;
 #[allow(unused_imports)]
 use colored::Colorize;
 
-use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
-use super::pbs;
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl, ParmEncrWord};
+use super::{pbs, signum};
 
+/// Rounding mode (at given position, i.e., of `X / 2^pos`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoundingMode {
+    /// to nearest, ties toward +∞ (the default of `round_at`)
+    HalfUp,
+    /// to nearest, ties away from zero
+    HalfAway,
+    /// to nearest, ties to even
+    HalfEven,
+    /// toward -∞
+    Floor,
+    /// toward +∞
+    Ceil,
+    /// toward zero (truncation)
+    TowardZero,
+}
+
+impl RoundingMode {
+    /// Auxiliary bit `w` needed by the rounding rule:
+    /// sign of `X` (`w = 1` iff `X ≥ 0`), or parity of `X / 2^pos` (`w = 1` iff odd)
+    fn aux(&self) -> RoundingAux {
+        match self {
+            RoundingMode::HalfUp | RoundingMode::Floor | RoundingMode::Ceil => RoundingAux::None,
+            RoundingMode::HalfAway | RoundingMode::TowardZero                 => RoundingAux::NonNeg,
+            RoundingMode::HalfEven                                            => RoundingAux::Parity,
+        }
+    }
+
+    /// Rounding rule: value `r` to be added at position `pos`
+    /// * `y` is the digit at `pos-1`, `s` the signum of lower digits, `w` the auxiliary bit
+    /// * n.b., the remainder `L` (digits below `pos`) has sign `y` (or `s` if `y = 0`), and it is a tie iff `y ≠ 0` & `s = 0`
+    fn rule(&self, y: i32, s: i32, w: i32) -> i32 {
+        let l_sgn = if y != 0 {y} else {s};
+        // compare L with ±1/2: above +1/2 / tie / below -1/2 / tie
+        let above = y == 1 && s == 1;
+        let tie_p = y == 1 && s == 0;
+        let below = y == -1 && s == -1;
+        let tie_n = y == -1 && s == 0;
+        match self {
+            RoundingMode::Floor         => if l_sgn < 0 {-1} else {0},
+            RoundingMode::Ceil          => if l_sgn > 0 { 1} else {0},
+            RoundingMode::TowardZero    => if w == 1 {RoundingMode::Floor.rule(y, s, w)} else {RoundingMode::Ceil.rule(y, s, w)},
+            RoundingMode::HalfUp        => if above || tie_p {1} else if below {-1} else {0},
+            RoundingMode::HalfAway      => if above || (tie_p && w == 1) {1} else if below || (tie_n && w == 0) {-1} else {0},
+            RoundingMode::HalfEven      => if above || (tie_p && w == 1) {1} else if below || (tie_n && w == 1) {-1} else {0},
+        }
+    }
+
+    /// LUT for `2y + s + 7w` (in `-3 ..= 10`, i.e., no negacyclic conflicts)
+    fn lut(&self) -> [u64; 1 << (5-1)] {
+        let mut lut = [0u64; 1 << (5-1)];
+        for y in -1..=1 {
+            for s in -1..=1 {
+                for w in 0..=1 {
+                    let idx = 2*y + s + 7*w;
+                    let r = self.rule(y, s, w);
+                    // negative index: f(idx) = -f(idx + 16)
+                    let (i, v) = if idx >= 0 {(idx, r)} else {(idx + 16, -r)};
+                    lut[i as usize] = v.rem_euclid(1 << 5) as u64;
+                }
+            }
+        }
+        lut
+    }
+}
+
+/// Auxiliary bit of rounding rule
+enum RoundingAux {
+    None,
+    NonNeg,
+    Parity,
+}
+
+/// Implementation of rounding at given position (half-up)
 pub fn round_at_impl(
     pc: &ParmesanCloudovo,
     x:  &ParmCiphertext,
     pos: usize,
 ) -> Result<ParmCiphertext, Box<dyn Error>> {
+    round_at_mode_impl(pc, x, pos, RoundingMode::HalfUp, false)
+}
+
+/// Implementation of rescaling: `round(X / 2^pos)` (half-up)
+/// * rounding yields zeros at the lowest `pos` words, which are dropped
+pub fn rescale_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    pos: usize,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    let rx = round_at_impl(pc, x, pos)?;
+    Ok(if rx.len() > pos {rx[pos..].to_vec()} else {ParmCiphertext::empty()})
+}
+
+/// Implementation of rounding at given position with given mode
+/// * `noisy` skips the refresh of the final addition (only when the result gets immediately decrypted)
+pub fn round_at_mode_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    pos: usize,
+    mode: RoundingMode,
+    noisy: bool,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
 
     match pos {
         // no rounding needed
@@ -23,7 +120,8 @@ pub fn round_at_impl(
         // consider if this behavior is desired:
         // p if p > PARM_CT_MAXLEN => Err(format!("Rounding position > ParmCiphertext max length {}.", PARM_CT_MAXLEN).into()),
         // rounding 1 digit after x.len() -> return triv of length 1 (as in multiplication of empty ciphertexts)
-        p if p >= x.len() + 1 => Ok(ParmArithmetics::zero()),
+        // (n.b., only to nearest; directed rounding of a negative/positive value gives ∓2^pos)
+        p if p >= x.len() + 1 && matches!(mode, RoundingMode::HalfUp | RoundingMode::HalfAway | RoundingMode::HalfEven) => Ok(ParmArithmetics::zero()),
 
         // otherwise, do the job
 
@@ -40,30 +138,56 @@ pub fn round_at_impl(
         //    -1   |-1 | 0 | 0 |
         //
         // add: 2y + s == 2, 3 .. +1 or 2y + s == -3 .. -1 otherwise 0
+        //
+        // other modes: add 7w, where w is an auxiliary bit (sign of X, or parity of X / 2^pos), cf. `RoundingMode::rule`
         _ => {
+            // pad with trivial zeros (directed rounding beyond the length)
+            let mut xp = x.clone();
+            while xp.len() < pos {xp.push(ParmEncrWord::encrypt_word_triv(pc.pub_keys, 0));}
+
             measure_duration!(
-                ["Rounding (at {}-th index, {}-bit)", pos, x.len()],
+                ["Rounding (at {}-th index, {}-bit, {:?})", pos, x.len(), mode],
                 [
-                    let s = ParmArithmetics::sgn(pc, &x[0..pos-1].to_vec());
+                    // signum of lower digits & auxiliary bit (7w)
+                    let (s, w7) = match mode.aux() {
+                        RoundingAux::None   => (ParmArithmetics::sgn(pc, &xp[0..pos-1].to_vec()), None),
+                        RoundingAux::NonNeg => {
+                            let nn = signum::nonneg_impl(pc, &xp)?;
+                            (ParmArithmetics::sgn(pc, &xp[0..pos-1].to_vec()), Some(pbs::f_1__pi_5__with_val(pc, &nn, 7)))
+                        },
+                        RoundingAux::Parity => {
+                            let par = if pos < xp.len() {Some(pbs::a_1__pi_5__with_val(pc, &xp[pos], 7))} else {None};
+                            (ParmArithmetics::sgn(pc, &xp[0..pos-1].to_vec()), par)
+                        },
+                    };
                     // calc 2y
-                    let mut yy_s = x[pos-1].mul_const(2);
-                    // 2y + s
-                    if s.len() > 0 {yy_s.add_inplace(&s[0]);}
+                    let mut yy_s = xp[pos-1].mul_const(2);
+                    // 2y + s (+ 7w)
+                    if !s.is_empty() {yy_s.add_inplace(&s[0]);}
+                    if let Some(w7i) = &w7 {yy_s.add_inplace(w7i);}
 
                     // factor that is to be added
                     let mut r = ParmCiphertext::triv(pos, pc);
-                    r.push(pbs::round_2y_s__pi_5(pc, &yy_s));
+                    r.push(if mode == RoundingMode::HalfUp {
+                        pbs::round_2y_s__pi_5(pc, &yy_s)
+                    } else {
+                        pbs::eval_LUT_5_uint(pc, &yy_s, mode.lut())
+                    });
 
                     // sliced input
                         // was:
                         //~ let mut slx = ParmCiphertext::triv(pos, &pc.pub_keys.encoder)?;
                         //~ slx.append(&mut x[pos..].to_vec());
                         // now:
-                    let slx = ParmArithmetics::shift(pc, &x[pos..].to_vec(), pos);
+                    let slx = ParmArithmetics::shift(pc, &xp[pos..].to_vec(), pos);
                 ]
             );
 
-            Ok(ParmArithmetics::add(pc, &slx, &r))
+            if noisy {
+                Ok(ParmArithmetics::add_noisy(pc, &slx, &r))
+            } else {
+                Ok(ParmArithmetics::add(pc, &slx, &r))
+            }
         }
     }
 }
@@ -5,6 +5,10 @@ use serde::{Serialize, Deserialize};
 
 use crate::*;
 
+// parallelization tools
+#[cfg(not(feature = "seq_analyze"))]
+use rayon::prelude::*;
+
 
 // =============================================================================
 //
@@ -13,7 +17,7 @@ use crate::*;
 
 /// element of ASC -- prescription (combination of previous):
 /// left addend's sign, left addend's index (within the ASC), <same for right addend>, right addend's shift
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct AddShift {
     pub l_pos:      bool,
     pub l_idx:      usize,
@@ -34,32 +38,43 @@ pub trait AscEval<T: ParmArithmetics> {
     ) -> Result<T, Box<dyn Error>>;
 }
 
-impl<T: ParmArithmetics + Clone> AscEval<T> for Asc
+impl<T: ParmArithmetics + Clone + Send + Sync> AscEval<T> for Asc
 {
     fn eval(
         &self,
         pc: &ParmesanCloudovo,
         x: &T,
     ) -> Result<T, Box<dyn Error>> {
-        let mut asc_vals = vec![x.clone()];
+        // placeholders, filled level by level
+        let mut asc_vals = vec![x.clone(); self.len() + 1];
 
-        // in short ASC's, there's only little space for parallelization -> not worth it
-        // instead, scalar mul by window values are parallelized
-        for adsh in self {
-            // +-1 * left_val  +  +-1 * right_val << right_shift
+        // steps of the same level only depend on previous levels -> evaluate them in parallel
+        // (n.b., scalar mul by window values are parallelized, too)
+        for lvl in self.levels() {
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let lvl_iter = lvl.par_iter();
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let lvl_iter = lvl.iter();
 
-            let neg_l = ParmArithmetics::opp(&asc_vals[adsh.l_idx]);
+            let lvl_vals: Vec<(usize, T)> = lvl_iter.map(|si| {
+                // +-1 * left_val  +  +-1 * right_val << right_shift
+                let adsh = &self[*si];
 
-            let r_sh = ParmArithmetics::shift(pc, &asc_vals[adsh.r_idx], adsh.r_shift);
-            let neg_r_sh = ParmArithmetics::opp(&r_sh);
+                let neg_l = ParmArithmetics::opp(&asc_vals[adsh.l_idx]);
 
-            // only limited space for parallelization
-            // (no example on the first sight)
-            asc_vals.push(
-                ParmArithmetics::add(&pc,
+                let r_sh = ParmArithmetics::shift(pc, &asc_vals[adsh.r_idx], adsh.r_shift);
+                let neg_r_sh = ParmArithmetics::opp(&r_sh);
+
+                (*si + 1, ParmArithmetics::add(pc,
                     if adsh.l_pos {&asc_vals[adsh.l_idx]} else {&neg_l},
-                    if adsh.r_pos {&r_sh} else {&neg_r_sh})
-            );
+                    if adsh.r_pos {&r_sh} else {&neg_r_sh}))
+            }).collect();
+
+            for (vi, val) in lvl_vals {
+                asc_vals[vi] = val;
+            }
         }
         //          Option<&T>   &T       T
         Ok(asc_vals.last()      .unwrap().clone())
@@ -67,38 +82,80 @@ impl<T: ParmArithmetics + Clone> AscEval<T> for Asc
 }
 
 pub trait AscValue {
+    /// Depths of all elements of ASC (incl. the initial `1` of depth 0)
+    fn depths(&self) -> Vec<usize>;
+
     /// Value (i64) of ASC
     fn value(
         &self,
         pc: &ParmesanCloudovo,
     ) -> i64;
 
+    /// Value (i64) of ASC, evaluated without `ParmesanCloudovo`
+    fn value_i64(&self) -> i64;
+
+    /// Depth of ASC, i.e., the number of additions on the longest path from `1` to the result
+    fn depth(&self) -> usize;
+
+    /// Dependency DAG of ASC in levels: indices of steps whose results are of depth 1, 2, ...
+    fn levels(&self) -> Vec<Vec<usize>>;
+
     /// Load from YAML file
     fn map_from_yaml(
         bitlen: usize,
         filename: &str,
     ) -> Result<BTreeMap<usize, Self>, Box<dyn Error>> where Self: Sized;
+
+    /// Load from YAML string
+    fn map_from_yaml_str(
+        bitlen: usize,
+        yaml_str: &str,
+    ) -> Result<BTreeMap<usize, Self>, Box<dyn Error>> where Self: Sized;
 }
 
 impl AscValue for Asc {
+    fn depths(&self) -> Vec<usize> {
+        let mut asc_depths = vec![0usize];
+        for adsh in self {
+            asc_depths.push(std::cmp::max(asc_depths[adsh.l_idx], asc_depths[adsh.r_idx]) + 1);
+        }
+        asc_depths
+    }
+
     fn value(
         &self,
         pc: &ParmesanCloudovo,
     ) -> i64 {
         self.eval(pc, &1i64).expect("Asc::value failed.")
     }
-    //~ fn value(&self) -> i64 {
-        //~ // destructuring assignments are unstable: issue #71126 <https://github.com/rust-lang/rust/issues/71126>
-        //~ // hence ParmesanCloudovo must be present
-        //~ self.eval(_, 1i64).expect("Asc::value failed.")
-    //~ }
+
+    fn value_i64(&self) -> i64 {
+        let mut asc_vals = vec![1i64];
+        for adsh in self {
+            let l = if adsh.l_pos {asc_vals[adsh.l_idx]} else {-asc_vals[adsh.l_idx]};
+            let r = if adsh.r_pos {asc_vals[adsh.r_idx]} else {-asc_vals[adsh.r_idx]};
+            asc_vals.push(l + (r << adsh.r_shift));
+        }
+        *asc_vals.last().unwrap()
+    }
+
+    fn depth(&self) -> usize {
+        *self.depths().last().unwrap()
+    }
+
+    fn levels(&self) -> Vec<Vec<usize>> {
+        let asc_depths = self.depths();
+        let mut lvls = vec![Vec::new(); self.depth()];
+        for (si, di) in asc_depths.iter().skip(1).enumerate() {
+            lvls[di - 1].push(si);
+        }
+        lvls
+    }
 
     fn map_from_yaml(
         bitlen: usize,
         filename: &str,
     ) -> Result<BTreeMap<usize, Self>, Box<dyn Error>> {
-        let asc_map: BTreeMap<usize, Self>;
-
         // check if YAML file exists
         if Path::new(filename).is_file() {
             println!("(i) Loading ASC's from '{}' ...", filename);
@@ -106,23 +163,28 @@ impl AscValue for Asc {
             // read YAML file
             let yaml_str = fs::read_to_string(filename)?;
 
-            // load map of ASC's from YAML string
-            asc_map = serde_yaml::from_str(&yaml_str)?;
-
+            Self::map_from_yaml_str(bitlen, &yaml_str)
         } else {
-            return Err(format!("ASC file '{}' does not exist.", filename).into());
+            Err(format!("ASC file '{}' does not exist.", filename).into())
         }
+    }
+
+    fn map_from_yaml_str(
+        bitlen: usize,
+        yaml_str: &str,
+    ) -> Result<BTreeMap<usize, Self>, Box<dyn Error>> {
+        // load map of ASC's from YAML string
+        let asc_map: BTreeMap<usize, Self> = serde_yaml::from_str(yaml_str)?;
 
         // number of elements = 2^bitlen / 2
         if asc_map.len() != (1 << bitlen) / 2 {return Err(format!("Wrong number of ASC's: {}, expected {} (n.b., '1: []' is expected, too).", asc_map.len(), (1 << bitlen) / 2).into());}
 
         // check correctness of chains
-        //TODO ASC eval without ParmesanCloudovo
-        //~ for (n, asc) in asc_map.iter() {
-            //~ if *n as i64 != asc.value_i64() {
-                //~ return Err(format!("Incorrect ASC value: '{:?}' evaluates to {}, expected {}.", asc, asc.value_i64(), *n as i64).into());
-            //~ }
-        //~ }
+        for (n, asc) in asc_map.iter() {
+            if *n as i64 != asc.value_i64() {
+                return Err(format!("Incorrect ASC value: '{:?}' evaluates to {}, expected {}.", asc, asc.value_i64(), *n as i64).into());
+            }
+        }
 
         Ok(asc_map)
     }
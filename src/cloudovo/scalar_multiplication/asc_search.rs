@@ -0,0 +1,197 @@
+use std::error::Error;
+use std::fs;
+
+use crate::*;
+
+
+// =============================================================================
+//
+//  Search for Addition-Subtraction Chains
+//
+
+/// Objective of ASC search
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AscObjective {
+    /// minimal number of additions (ties broken by depth)
+    Length,
+    /// minimal depth, i.e., the number of subsequent additions (ties broken by the number of additions)
+    Depth,
+}
+
+/// Partial chain: values & depths of its elements (incl. the initial `1`), and the ASC that builds them
+#[derive(Clone)]
+struct PartChain {
+    vals:   Vec<u64>,
+    depths: Vec<usize>,
+    asc:    Asc,
+}
+
+impl PartChain {
+    fn unit() -> PartChain {
+        PartChain {
+            vals:   vec![1],
+            depths: vec![0],
+            asc:    Vec::new(),
+        }
+    }
+
+    /// Chains with the same elements (of the same depths) are equivalent, regardless of their order
+    fn key(&self) -> Vec<(u64, usize)> {
+        let mut k: Vec<(u64, usize)> = self.vals.iter().copied().zip(self.depths.iter().copied()).collect();
+        k.sort_unstable();
+        k
+    }
+
+    /// Call `f` for every new element `±l ± r·2^s` (positive, odd, below `bound` & not deeper than `max_depth`)
+    fn for_each_ext<F: FnMut(u64, usize, AddShift)>(
+        &self,
+        bound: u64,
+        max_depth: usize,
+        mut f: F,
+    ) {
+        for (l_idx, l) in self.vals.iter().enumerate() {
+            for (r_idx, r) in self.vals.iter().enumerate() {
+                let d = std::cmp::max(self.depths[l_idx], self.depths[r_idx]) + 1;
+                if d > max_depth {continue;}
+
+                // n.b., odd ± even is odd, hence r_shift ≥ 1 always gives a new odd value
+                let mut r_shift = 1usize;
+                while (r << r_shift) < bound + l {
+                    let r_sh = r << r_shift;
+                    //  l + r·2^s,  -l + r·2^s,  l - r·2^s  (-l - r·2^s is negative)
+                    for (l_pos, r_pos) in [(true, true), (false, true), (true, false)] {
+                        let v = match (l_pos, r_pos) {
+                            (true, true)                => l + r_sh,
+                            (false, true) if r_sh > *l  => r_sh - l,
+                            (true, false) if *l > r_sh  => l - r_sh,
+                            _ => continue,
+                        };
+                        if v < bound && !self.vals.contains(&v) {
+                            f(v, d, AddShift {l_pos, l_idx, r_pos, r_idx, r_shift});
+                        }
+                    }
+                    r_shift += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Minimal depth of an ASC for `n`: `⌈log_2 w⌉`, where `w` is the NAF weight of `n`
+/// * a chain of depth `d` sums at most `2^d` signed powers of two, and the NAF has the minimal weight
+/// * this depth is always reached by a balanced tree of NAF digits
+pub fn min_depth(n: u64) -> usize {
+    // NAF weight
+    let mut w = 0usize;
+    let mut m = n as i128;
+    while m != 0 {
+        if m & 1 == 1 {
+            // digit ±1 such that (m - digit) is divisible by 4
+            m -= 2 - (m & 3);
+            w += 1;
+        }
+        m >>= 1;
+    }
+
+    let mut d = 0usize;
+    while (1 << d) < w {d += 1;}
+    d
+}
+
+/// Search optimal ASC's for all odd values below `2^bitlen`
+/// * chains are extended by one addition at a time (breadth-first), equivalent chains are merged
+/// * the search is exhaustive for intermediate values below `2^(bitlen+1)`
+/// * every chain is verified with `AscValue::value_i64`
+pub fn search_ascs(
+    bitlen: usize,
+    objective: AscObjective,
+) -> Result<BTreeMap<usize, Asc>, Box<dyn Error>> {
+    if bitlen == 0 || bitlen > 24 {
+        return Err(format!("ASC search for {}-bit values not supported.", bitlen).into());
+    }
+
+    let n_max: u64 = 1 << bitlen;
+    let bound: u64 = n_max << 1;
+    let n_targets = (n_max / 2) as usize;
+
+    // minimal depths of targets (indexed by value)
+    let min_depths: Vec<usize> = (0..n_max).map(|n| if n & 1 == 1 {min_depth(n)} else {0}).collect();
+    // deeper elements are useless for depth-optimal chains
+    let max_depth = match objective {
+        AscObjective::Length => usize::MAX,
+        AscObjective::Depth  => *min_depths.iter().max().unwrap(),
+    };
+
+    let mut best: BTreeMap<u64, Asc> = BTreeMap::new();
+    best.insert(1, Vec::new());
+
+    let mut level = vec![PartChain::unit()];
+    let mut len = 0usize;
+
+    while best.len() < n_targets {
+        len += 1;
+
+        // look for targets among one-step extensions of chains of current length
+        let mut found: BTreeMap<u64, Asc> = BTreeMap::new();
+        for c in &level {
+            c.for_each_ext(bound, max_depth, |v, d, adsh| {
+                if v >= n_max || best.contains_key(&v) {return;}
+                // depth-optimal chains must reach the minimal depth
+                if objective == AscObjective::Depth && d != min_depths[v as usize] {return;}
+                // among chains of the same length, prefer shallower
+                if found.get(&v).is_none_or(|asc| d < asc.depth()) {
+                    let mut asc = c.asc.clone();
+                    asc.push(adsh);
+                    found.insert(v, asc);
+                }
+            });
+        }
+        best.append(&mut found);
+
+        if best.len() == n_targets {break;}
+
+        // extend chains of current length (only if there are targets left)
+        // (n.b., BTreeMap keeps the search deterministic)
+        let mut next: BTreeMap<Vec<(u64, usize)>, PartChain> = BTreeMap::new();
+        for c in &level {
+            c.for_each_ext(bound, max_depth, |v, d, adsh| {
+                let mut nc = c.clone();
+                nc.vals.push(v);
+                nc.depths.push(d);
+                nc.asc.push(adsh);
+                next.entry(nc.key()).or_insert(nc);
+            });
+        }
+        level = next.into_values().collect();
+
+        if level.is_empty() {
+            return Err(format!("ASC search failed: {} values without chain after {} additions.", n_targets - best.len(), len).into());
+        }
+        println!("(i) ASC search: {} of {} values found with ≤ {} additions, {} chains to extend ...", best.len(), n_targets, len, level.len());
+    }
+
+    // verify chains
+    for (n, asc) in best.iter() {
+        if *n as i64 != asc.value_i64() {
+            return Err(format!("Incorrect ASC value: '{:?}' evaluates to {}, expected {}.", asc, asc.value_i64(), n).into());
+        }
+    }
+
+    Ok(best.into_iter().map(|(n, asc)| (n as usize, asc)).collect())
+}
+
+/// Emit map of ASC's as YAML (as consumed by `Asc::map_from_yaml`)
+pub fn ascs_to_yaml(
+    asc_map: &BTreeMap<usize, Asc>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(serde_yaml::to_string(asc_map)?)
+}
+
+/// Write map of ASC's into a YAML file
+pub fn write_ascs_yaml(
+    asc_map: &BTreeMap<usize, Asc>,
+    filename: &str,
+) -> Result<(), Box<dyn Error>> {
+    fs::write(filename, ascs_to_yaml(asc_map)?)?;
+    Ok(())
+}
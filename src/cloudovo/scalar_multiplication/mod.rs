@@ -3,7 +3,7 @@ use std::error::Error;
 pub use std::fs::{self,File,OpenOptions};
 pub use std::path::Path;
 pub use std::io::Write;
-pub use std::collections::BTreeMap;
+pub use std::collections::{BTreeMap, BTreeSet};
 
 use crate::*;
 
@@ -15,79 +15,47 @@ use rayon::prelude::*;
 use colored::Colorize;
 
 use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
+use super::addition;
 
 pub mod asc;
 pub use asc::{Asc, AddShift, AscEval, AscValue};
+pub mod asc_search;
 pub mod naf;
+pub mod recoding;
+pub use recoding::ScalarRecoding;
 
-/// Implementation of signum via parallel reduction
-pub fn scalar_mul_impl(
-    pc: &ParmesanCloudovo,
-    k: i32,
-    x: &ParmCiphertext,
-) -> Result<ParmCiphertext, Box<dyn Error>> {
+/// Objective of scalar multiplication
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScmObjective {
+    /// minimal number of bootstraps, i.e., length-optimal ASC's
+    Throughput,
+    /// minimal bootstrap depth, i.e., depth-optimal ASC's
+    Latency,
+}
 
-    // move sign of k to x, prepare both +1 and -1 multiples
-    let mut x_pos = ParmCiphertext::empty();
-    let mut x_neg = ParmCiphertext::empty();
-    for xi in x {
-        if k >= 0 {
-            x_pos.push(xi.clone());
-            x_neg.push(xi.opp());
-        } else {
-            x_pos.push(xi.opp());
-            x_neg.push(xi.clone());
+impl ScmObjective {
+    /// ASC's for window values
+    pub fn ascs(&self) -> &'static BTreeMap<usize, Asc> {
+        match self {
+            ScmObjective::Throughput => &ASC_12,
+            ScmObjective::Latency    => &ASC_12_DEPTH,
         }
     }
-    // from now on, only work with k_abs (the sign is already moved to x)
-    let k_abs = k.abs() as u32;
-
-    // resolve |k| < 2
-    if k_abs == 0 {return Ok(ParmCiphertext::empty());}
-    if k_abs == 1 {return Ok(x_pos);}
-
-
-    // ====    Sliding Window    ===============================================
-
-    // sliding window
-    let ws = naf::wind_shifts(k_abs, ASC_BITLEN);  // pairs (window value, shift), built-up from certain NAF (or other repre)
-
-    // pre-compute products: window_val * x
-    // do not calculate twice -> store in map
-    let mut wiabs_wix_map: BTreeMap<u32, ParmCiphertext> = BTreeMap::new();
-    // init keys
-    for (wi, _shi) in &ws {
-        if !wiabs_wix_map.contains_key(&(wi.abs() as u32)) {
-            // prepare with empty ciphertexts that will be filled later
-            wiabs_wix_map.insert(wi.abs() as u32, ParmCiphertext::empty());
-        }
-    }
-
-    // calc values (wi * x) in parallel
-    // parallel iterators
-    #[cfg(not(feature = "seq_analyze"))]
-    let wa_wi_iter = wiabs_wix_map.par_iter_mut();
-    // sequential iterators
-    #[cfg(feature = "seq_analyze")]
-    let wa_wi_iter = wiabs_wix_map.iter_mut();
+}
 
-    wa_wi_iter.for_each(|(wiabs, wi_x)| {
-        let wi_asc = &ASC_12[&(*wiabs as usize)];
-        println!("(i) Evaluating ASC for {wiabs} ...", );
-        *wi_x = wi_asc.eval(pc, &x_pos).expect("Asc::eval failed.");   // due to wi.abs, x_pos must be taken
-    });
+/// Implementation of scalar multiplication via sliding window & ASC's
+pub fn scalar_mul_impl(
+    pc: &ParmesanCloudovo,
+    k: i64,
+    x: &ParmCiphertext,
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    // scalar multiplication array (empty for k == 0)
+    let mulary = dot_const_mulary(pc, &[k], std::slice::from_ref(x), objective, recoding)?;
 
-    // init mulary
-    let mut mulary: Vec<ParmCiphertext> = vec![ParmCiphertext::empty(); ws.len()];
-    // fill with pre-computed values from wiabs_wix_map, shift & negate accordingly
-    mulary.iter_mut().zip(ws.iter()).for_each( |(mi, (wi, shi))| {
-        *mi = if *wi < 0 {
-            let neg_wi_x = ParmArithmetics::opp(&wiabs_wix_map[&(wi.abs() as u32)]);
-            ParmArithmetics::shift(pc, &neg_wi_x, *shi)
-        } else {
-            ParmArithmetics::shift(pc, &wiabs_wix_map[&(wi.abs() as u32)], *shi)
-        };
-    });
+    // resolve k == 0
+    if mulary.is_empty() {return Ok(ParmCiphertext::empty());}
 
     // ====    Standard NAF    =================================================
 
@@ -111,25 +79,234 @@ pub fn scalar_mul_impl(
         return Ok(mulary[0].clone());
     }
 
-    //TODO
-    //  check if there are no subsequent lines of len & len+1 (with NAF, thisfollows from the fact that there are no neighboring non-zeros),
-    //  then this mulary might be reduced in parallel (carefully; up to the last row which must be added in the last step)
-
     // reduce mulary
+    // n.b., a sequential chain has depth of the number of rows, whereas a pairwise tree (shortest rows first,
+    //       the longest row is kept for last) only has logarithmic depth, cf. `addition::sum_impl`
+    let res;
+    measure_duration!(
+        ["Scalar multiplication (non-triv ±{} · {}-bit)", k.unsigned_abs(), x.len()],
+        [
+            res = addition::sum_impl(pc, &mulary)?;
+        ]
+    );
+
+    Ok(res)
+}
+
+/// Expected bootstrap depth of scalar multiplication by `k`
+/// * window multiples are evaluated by ASC's in parallel, then rows are reduced by a pairwise tree
+/// * each addition takes two subsequent bootstraps
+pub fn scm_pbs_depth(
+    k: i64,
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<usize, Box<dyn Error>> {
+    let ws = signed_windows(k, objective, recoding)?;
+    // n.b., no bootstrap for a single ±1 window (i.e., k = ±2^t)
+    if ws.len() == 1 && ws[0].0.unsigned_abs() == 1 {return Ok(0);}
+    let (_adds, depth) = recoding::windows_cost(&ws, objective);
+    Ok(2 * depth)
+}
+
+/// Implementation of dot product with constant weights: `Σ w_i·X_i + b`
+/// * all window multiples are evaluated at once (in parallel), cf. `dot_const_mulary`
+/// * all rows (incl. bias) are reduced by a single multi-operand addition
+pub fn dot_const_impl(
+    pc: &ParmesanCloudovo,
+    ws: &[i64],
+    xs: &[ParmCiphertext],
+    b: i64,
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    if ws.len() != xs.len() {
+        return Err(format!("Dot product of vectors of different lengths: {} weights, {} ciphertexts.", ws.len(), xs.len()).into());
+    }
+
+    let res;
+
+    measure_duration!(
+        ["Dot product with constant weights ({} items)", ws.len()],
+        [
+            let mut mulary = dot_const_mulary(pc, ws, xs, objective, recoding)?;
+            // bias as a row of trivial words
+            mulary.push(addition::add_const_impl(pc, &ParmCiphertext::empty(), b)?);
+
+            res = addition::sum_impl(pc, &mulary)?;
+        ]
+    );
+
+    Ok(res)
+}
+
+/// Implementation of multi-scalar multiplication: `k_1·X, k_2·X, ...`
+/// * scalars of the same odd part (i.e., `±2^t·k'`) share the product `k'·X` (it is only shifted & negated)
+/// * window multiples `|w|·X` of all scalars are evaluated at once (in parallel), each of them only once
+/// * products of distinct odd parts are reduced in parallel, each by a multi-operand addition
+pub fn multi_scalar_mul_impl(
+    pc: &ParmesanCloudovo,
+    ks: &[i64],
+    x: &ParmCiphertext,
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<Vec<ParmCiphertext>, Box<dyn Error>> {
+    let xs = std::slice::from_ref(x);
+
+    // distinct odd parts of scalars -> their signed windows
+    let mut kodd_ws: BTreeMap<u64, Vec<(i32, usize)>> = BTreeMap::new();
+    for ki in ks {
+        if *ki == 0 {continue;}
+        let k_odd = ki.unsigned_abs() >> ki.trailing_zeros();
+        if let std::collections::btree_map::Entry::Vacant(e) = kodd_ws.entry(k_odd) {
+            e.insert(signed_windows(k_odd as i64, objective, recoding)?);
+        }
+    }
+
+    let mut kodd_kx: BTreeMap<u64, ParmCiphertext> = kodd_ws.keys().map(|ko| (*ko, ParmCiphertext::empty())).collect();
+
     measure_duration!(
-        ["Scalar multiplication (non-triv ±{} · {}-bit)", k_abs, x.len()],
+        ["Multi-scalar multiplication ({} scalars, {} distinct odd parts · {}-bit)", ks.len(), kodd_ws.len(), x.len()],
         [
-            // reduce multiplication array (of length ≥ 2)
-            let mut intmd = vec![ParmCiphertext::empty(); 2];
-            let mut idx = 0usize;
-            intmd[idx] = ParmArithmetics::add(pc, &mulary[0], &mulary[1]);
-
-            for i in 2..mulary.len() {
-                idx ^= 1;
-                intmd[idx] = ParmArithmetics::add(pc, &intmd[idx ^ 1], &mulary[i]);
-            }
+            // window multiples of all scalars
+            let wiabs_wix_map = eval_wind_mults(
+                pc,
+                kodd_ws.values().flatten().map(|(wi, _shi)| (0usize, wi.unsigned_abs())).collect(),
+                xs,
+                objective,
+            );
+
+            // reduce rows of each odd part
+            // parallel iterators
+            #[cfg(not(feature = "seq_analyze"))]
+            let ko_kx_iter = kodd_kx.par_iter_mut();
+            // sequential iterators
+            #[cfg(feature = "seq_analyze")]
+            let ko_kx_iter = kodd_kx.iter_mut();
+
+            ko_kx_iter.for_each(|(ko, kx)| {
+                let mulary: Vec<ParmCiphertext> = kodd_ws[ko].iter().map(|(wi, shi)| {
+                    wind_mult_row(pc, &wiabs_wix_map[&(0, wi.unsigned_abs())], *wi, *shi)
+                }).collect();
+                *kx = addition::sum_impl(pc, &mulary).expect("sum_impl failed.");
+            });
         ]
     );
 
-    Ok(intmd[idx].clone())
+    // shift & negate products of odd parts
+    Ok(ks.iter().map(|ki| {
+        if *ki == 0 {return ParmCiphertext::empty();}
+        let k_odd = ki.unsigned_abs() >> ki.trailing_zeros();
+        let kx = ParmArithmetics::shift(pc, &kodd_kx[&k_odd], ki.trailing_zeros() as usize);
+        if *ki < 0 {ParmArithmetics::opp(&kx)} else {kx}
+    }).collect())
+}
+
+/// Multiplication array of a dot product with constant weights: `Σ k_i·X_i`
+/// * each k_i is split into (signed) windows, cf. `signed_windows`
+/// * window multiples `|w|·X_i` are evaluated via ASC's in parallel (across all weights), each of them only once
+/// * rows are window multiples, shifted & negated accordingly
+pub fn dot_const_mulary(
+    pc: &ParmesanCloudovo,
+    ks: &[i64],
+    xs: &[ParmCiphertext],
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<Vec<ParmCiphertext>, Box<dyn Error>> {
+
+    // triples (operand index, signed window value, shift)
+    let mut iws: Vec<(usize, i32, usize)> = Vec::new();
+    for (i, ki) in ks.iter().enumerate() {
+        for (wi, shi) in signed_windows(*ki, objective, recoding)? {
+            iws.push((i, wi, shi));
+        }
+    }
+
+    // pre-compute products: window_val * x
+    let wiabs_wix_map = eval_wind_mults(
+        pc,
+        iws.iter().map(|(i, wi, _shi)| (*i, wi.unsigned_abs())).collect(),
+        xs,
+        objective,
+    );
+
+    // fill mulary with pre-computed values from wiabs_wix_map, shift & negate accordingly
+    let mulary = iws.iter().map(|(i, wi, shi)| {
+        wind_mult_row(pc, &wiabs_wix_map[&(*i, wi.unsigned_abs())], *wi, *shi)
+    }).collect();
+
+    Ok(mulary)
+}
+
+/// Signed windows of k: pairs (window value, shift), built-up from certain NAF (or other repre)
+/// * given recoding is used, or the one of the lowest estimated cost (if `None`)
+/// * sign of k is moved to window values
+fn signed_windows(
+    k: i64,
+    objective: ScmObjective,
+    recoding: Option<&dyn ScalarRecoding>,
+) -> Result<Vec<(i32, usize)>, Box<dyn Error>> {
+    let k_abs = k.unsigned_abs();
+
+    // resolve |k| < 2
+    if k_abs == 0 {return Ok(Vec::new());}
+    if k_abs == 1 {return Ok(vec![(k.signum() as i32, 0)]);}
+
+    // ====    Sliding Window    ===============================================
+
+    let ws = match recoding {
+        Some(rc) => rc.windows(k_abs),
+        None     => recoding::pick_recoding(k_abs, objective).windows(k_abs),
+    };
+
+    let mut sws: Vec<(i32, usize)> = Vec::new();
+    for (wi, shi) in ws {
+        if wi % 2 == 0 || wi.unsigned_abs() as usize >= 1 << ASC_BITLEN {
+            return Err(format!("No ASC for window value {} (of {}).", wi, k).into());
+        }
+        sws.push((k.signum() as i32 * wi, shi));
+    }
+
+    Ok(sws)
+}
+
+/// Evaluate window multiples `|w|·X_i` for given keys `(i, |w|)` via ASC's (in parallel)
+fn eval_wind_mults(
+    pc: &ParmesanCloudovo,
+    keys: BTreeSet<(usize, u32)>,
+    xs: &[ParmCiphertext],
+    objective: ScmObjective,
+) -> BTreeMap<(usize, u32), ParmCiphertext> {
+    // do not calculate twice -> store in map
+    // init keys, prepare with empty ciphertexts that will be filled later
+    let mut wiabs_wix_map: BTreeMap<(usize, u32), ParmCiphertext> = keys.into_iter().map(|key| (key, ParmCiphertext::empty())).collect();
+
+    // calc values (wi * x) in parallel
+    // parallel iterators
+    #[cfg(not(feature = "seq_analyze"))]
+    let wa_wi_iter = wiabs_wix_map.par_iter_mut();
+    // sequential iterators
+    #[cfg(feature = "seq_analyze")]
+    let wa_wi_iter = wiabs_wix_map.iter_mut();
+
+    wa_wi_iter.for_each(|((i, wiabs), wi_x)| {
+        let wi_asc = &objective.ascs()[&(*wiabs as usize)];
+        println!("(i) Evaluating ASC for {wiabs} ...", );
+        *wi_x = wi_asc.eval(pc, &xs[*i]).expect("Asc::eval failed.");
+    });
+
+    wiabs_wix_map
+}
+
+/// Row of mul-array: window multiple `|w|·X`, negated (if `w < 0`) & shifted
+fn wind_mult_row(
+    pc: &ParmesanCloudovo,
+    wi_x: &ParmCiphertext,
+    wi: i32,
+    shi: usize,
+) -> ParmCiphertext {
+    if wi < 0 {
+        ParmArithmetics::shift(pc, &ParmArithmetics::opp(wi_x), shi)
+    } else {
+        ParmArithmetics::shift(pc, wi_x, shi)
+    }
 }
@@ -1,21 +1,34 @@
 use crate::userovo::encryption;
 
+/// Sliding window over Koyama-Tsuruoka "NAF"
 pub fn wind_shifts(
-    k: u32,
+    k: u64,
     bitlen: usize,
 ) -> Vec<(i32, usize)> {
-    // pairs of window values and shifts, built-up from certain NAF (or other repre)
-    let mut ws: Vec<(i32, usize)> = Vec::new();
-
     // Koyama-Tsuruoka "NAF" .. longer sections of zeros with the same Hamming weight as an ordinary NAF
     let k_vec = koyama_tsuruoka_vec(k);
 
+    vec_wind_shifts(&k_vec, bitlen)
+}
+
+/// Sliding window over a signed binary representation (LSB first)
+/// * returns pairs of window values and shifts, each window starts with a non-zero (i.e., window values are odd)
+pub fn vec_wind_shifts(
+    k_vec: &[i32],
+    bitlen: usize,
+) -> Vec<(i32, usize)> {
+    // pairs of window values and shifts, built-up from certain NAF (or other repre)
+    let mut ws: Vec<(i32, usize)> = Vec::new();
+
     // sliding window
     let mut sh = 0usize;
     loop {
         // find next non-zero (short circuit eval)
         while sh < k_vec.len() && k_vec[sh] == 0 {sh += 1;}
 
+        // whole vector processed
+        if sh >= k_vec.len() {break;}
+
         // take window of size bitlen -> convert to scalar -> push to result (n.b.! Rust's ranges!)
         let w = k_vec[sh..=(if sh + bitlen - 1 >= k_vec.len() {k_vec.len()-1} else {sh + bitlen-1})].to_vec();
         let wi = encryption::convert_from_vec(&w).expect("encryption::convert failed.");
@@ -23,67 +36,55 @@ pub fn wind_shifts(
 
         // increment shift/index
         sh += bitlen;
-
-        // whole vector processed
-        if sh >= k_vec.len() {break;}
     }
 
     ws
 }
 
-/// Standard NAF (Non-Adjacent Form)
-pub fn naf_vec(k: u32) -> Vec<i32> {
+/// Standard NAF (Non-Adjacent Form), by Algorithm 9.14 from ECC book (Cohen, Frey)
+pub fn naf_vec(k: u64) -> Vec<i32> {
 
-    // resolve trivial cases
+    // resolve trivial case
     if k == 0 {return vec![0];}
-    if k == 1 {return vec![1];}
-    // |k| < 2 resolved
 
-    //TODO implement variant by Algorithm 9.14 from ECC book (Cohen, Frey)
-    let k_len = encryption::bit_len_32(k);
+    let mut k_vec: Vec<i32> = Vec::new();
+    // n.b., k + 1 might not fit u64
+    let mut kk = k as i128;
+    while kk > 0 {
+        if kk & 1 == 1 {
+            // digit ±1 such that (kk - digit) is divisible by 4
+            let ki = 2 - (kk & 3) as i32;
+            kk -= ki as i128;
+            k_vec.push(ki);
+        } else {
+            k_vec.push(0);
+        }
+        kk >>= 1;
+    }
 
-    // k as a vector of bits
-    // replace sequences of 1's with 1|zeros|-1
-    //
-    // index   11  10   9   8   7   6   5   4   3   2   1   0
-    //
-    //          0   1   1   1   0   1   1   1   1   0   1   1
-    //          1   0   0  -1   1   0   0   0  -1   1   0  -1       first hit
-    //          1   0   0   0  -1   0   0   0   0  -1   0  -1       second hit
-    //
-    // e.g.: k = 0b11001110011110011011101111;
-    // first hit:   [-1, 0, 0, 0, 1, -1, 0, 0, 1, -1, 0, 1, 0, -1, 0, 0, 0, 1, 0, -1, 0, 0, 1, 0, -1, 0, 1]
-    // second hit:  [-1, 0, 0, 0, -1, 0, 0, 0, -1, 0, 0, 1, 0, -1, 0, 0, 0, 1, 0, -1, 0, 0, 1, 0, -1, 0, 1]
+    k_vec
+}
+
+/// Width-w NAF: odd digits `|d| < 2^(w-1)`, any two non-zeros separated by at least `w-1` zeros
+/// (for `w = 2`, this is the standard NAF)
+pub fn wnaf_vec(k: u64, w: usize) -> Vec<i32> {
+
+    // resolve trivial case
+    if k == 0 {return vec![0];}
 
     let mut k_vec: Vec<i32> = Vec::new();
-    let mut low_1: usize = 0;
-    for i in 0..=k_len {
-        // add a bit of k to the vector (including a leading zero)
-        k_vec.push(((k >> i) & 1) as i32);
-
-        if (k >> i) & 1 == 0 {
-            // at least two consecutive ones: i - low_1
-            if i - low_1 > 1 {
-                //  i             low_1
-                //  0   1   1   1   1   0
-                //  1   0   0   0  -1   0
-                //
-                // the new -1 can meet 1 from previous steps (if any): -1   1   0   =>  0  -1   0
-                if low_1 > 0 && k_vec[low_1-1] == 1 {
-                    k_vec[low_1-1] = -1;
-                    k_vec[low_1] = 0;
-                } else {
-                    k_vec[low_1] = -1;
-                }
-                for j in low_1+1..i {
-                    k_vec[j] = 0;
-                }
-                k_vec[i] = 1;
-            }
-            // move "pointer" forward
-            low_1 = i + 1;
+    let mut kk = k as i128;
+    while kk > 0 {
+        if kk & 1 == 1 {
+            // signed residue mod 2^w
+            let mut ki = (kk & ((1 << w) - 1)) as i32;
+            if ki >= 1 << (w - 1) {ki -= 1 << w;}
+            kk -= ki as i128;
+            k_vec.push(ki);
+        } else {
+            k_vec.push(0);
         }
-        // k == 1 .. keep "pointer" at its current/previous position -> do nothing
+        kk >>= 1;
     }
 
     k_vec
@@ -92,7 +93,7 @@ pub fn naf_vec(k: u32) -> Vec<i32> {
 /// Koyama-Tsuruoka "NAF" with:
 ///  - same Hamming weight as NAF
 ///  - greater average length of zeros
-pub fn koyama_tsuruoka_vec(k: u32) -> Vec<i32> {
+pub fn koyama_tsuruoka_vec(k: u64) -> Vec<i32> {
 
     // resolve trivial cases
     if k == 0 {return vec![0];}
@@ -100,7 +101,7 @@ pub fn koyama_tsuruoka_vec(k: u32) -> Vec<i32> {
     // |k| < 2 resolved
 
     //TODO implement as in ECC book
-    let k_len = encryption::bit_len_32(k);
+    let k_len = encryption::bit_len_64(k);
 
     // grows max by 1 index
     let mut k_vec: Vec<i32> = vec![0; k_len+1];
@@ -131,7 +132,7 @@ pub fn koyama_tsuruoka_vec(k: u32) -> Vec<i32> {
         } else {
             if v >= y+3 {
                 while j < u {
-                    k_vec[j] = (((k >> j) & 1) - 1) as i32;
+                    k_vec[j] = ((k >> j) & 1) as i32 - 1;
                     j += 1;
                 }
                 k_vec[j] = 1;   j += 1;
@@ -151,7 +152,7 @@ pub fn koyama_tsuruoka_vec(k: u32) -> Vec<i32> {
         k_vec[j+1] = m;
     } else {
         while j < u {
-            k_vec[j] = (((k >> j) & 1) - 1) as i32;
+            k_vec[j] = ((k >> j) & 1) as i32 - 1;
             j += 1;
         }
         k_vec[j] = 1;
@@ -0,0 +1,156 @@
+use std::collections::BTreeSet;
+
+use crate::*;
+use super::naf;
+use super::ScmObjective;
+
+
+// =============================================================================
+//
+//  Scalar Recodings
+//
+
+/// Recoding of a (non-negative) scalar for scalar multiplication
+pub trait ScalarRecoding: Sync {
+    /// Name of the recoding (for logging)
+    fn name(&self) -> String;
+
+    /// Signed binary representation of `k` (LSB first, digits in `{-1,0,1}`)
+    fn recode(&self, k: u64) -> Vec<i32>;
+
+    /// Pairs (window value, shift) such that `k = Σ w_i·2^(s_i)`,
+    /// window values are odd & in abs below `2^ASC_BITLEN` (i.e., there is an ASC for them)
+    fn windows(&self, k: u64) -> Vec<(i32, usize)>;
+}
+
+/// Standard NAF: windows are `±1`, i.e., no ASC's are needed
+pub struct Naf;
+
+/// Width-w NAF (`2 ≤ w ≤ ASC_BITLEN + 1`): windows are its (odd) digits
+pub struct WNaf {
+    pub w: usize,
+}
+
+/// Sliding window of given bit-length over Koyama-Tsuruoka "NAF"
+pub struct KoyamaTsuruoka {
+    pub bitlen: usize,
+}
+
+/// Sliding window of given bit-length over standard binary representation
+pub struct SlidingWindow {
+    pub bitlen: usize,
+}
+
+impl ScalarRecoding for Naf {
+    fn name(&self) -> String {String::from("NAF")}
+
+    fn recode(&self, k: u64) -> Vec<i32> {
+        naf::naf_vec(k)
+    }
+
+    fn windows(&self, k: u64) -> Vec<(i32, usize)> {
+        naf::vec_wind_shifts(&self.recode(k), 1)
+    }
+}
+
+impl ScalarRecoding for WNaf {
+    fn name(&self) -> String {format!("{}-NAF", self.w)}
+
+    fn recode(&self, k: u64) -> Vec<i32> {
+        // expand each digit d (at index i) into NAF of |d| (with the sign of d),
+        // which fits indexes i .. i+w-1, i.e., it does not overlap with the next non-zero digit
+        let kw_vec = naf::wnaf_vec(k, self.w);
+        let mut k_vec = vec![0i32; kw_vec.len() + self.w];
+        for (i, di) in kw_vec.iter().enumerate() {
+            if *di == 0 {continue;}
+            for (j, dj) in naf::naf_vec(di.unsigned_abs() as u64).iter().enumerate() {
+                k_vec[i + j] += di.signum() * dj;
+            }
+        }
+        // get rid of leading zeros
+        while k_vec.len() > 1 && k_vec.last() == Some(&0) {k_vec.pop();}
+        k_vec
+    }
+
+    fn windows(&self, k: u64) -> Vec<(i32, usize)> {
+        naf::wnaf_vec(k, self.w).iter().enumerate()
+            .filter(|(_i, di)| **di != 0)
+            .map(|(i, di)| (*di, i))
+            .collect()
+    }
+}
+
+impl ScalarRecoding for KoyamaTsuruoka {
+    fn name(&self) -> String {format!("Koyama-Tsuruoka ({}-bit windows)", self.bitlen)}
+
+    fn recode(&self, k: u64) -> Vec<i32> {
+        naf::koyama_tsuruoka_vec(k)
+    }
+
+    fn windows(&self, k: u64) -> Vec<(i32, usize)> {
+        naf::wind_shifts(k, self.bitlen)
+    }
+}
+
+impl ScalarRecoding for SlidingWindow {
+    fn name(&self) -> String {format!("Sliding window ({}-bit windows)", self.bitlen)}
+
+    fn recode(&self, k: u64) -> Vec<i32> {
+        if k == 0 {return vec![0];}
+        (0..encryption::bit_len_64(k)).map(|i| ((k >> i) & 1) as i32).collect()
+    }
+
+    fn windows(&self, k: u64) -> Vec<(i32, usize)> {
+        naf::vec_wind_shifts(&self.recode(k), self.bitlen)
+    }
+}
+
+/// Estimated cost of scalar multiplication by given windows: (number of additions, addition depth)
+/// * each window multiple is evaluated by an ASC once (even if it repeats)
+/// * window multiples are summed up by a tree of additions
+pub fn windows_cost(
+    ws: &[(i32, usize)],
+    objective: ScmObjective,
+) -> (usize, usize) {
+    let ascs = objective.ascs();
+    let wiabs: BTreeSet<usize> = ws.iter().map(|(wi, _shi)| wi.unsigned_abs() as usize).collect();
+
+    let asc_adds: usize = wiabs.iter().map(|wa| ascs[wa].len()).sum();
+    let asc_depth: usize = wiabs.iter().map(|wa| ascs[wa].depth()).max().unwrap_or(0);
+
+    let mut tree_depth = 0usize;
+    while (1 << tree_depth) < ws.len() {tree_depth += 1;}
+
+    (asc_adds + ws.len().saturating_sub(1), asc_depth + tree_depth)
+}
+
+/// Candidate recodings for automatic choice
+pub fn recoding_candidates() -> Vec<Box<dyn ScalarRecoding>> {
+    let mut rcs: Vec<Box<dyn ScalarRecoding>> = vec![
+        Box::new(Naf),
+        Box::new(KoyamaTsuruoka {bitlen: ASC_BITLEN}),
+        Box::new(SlidingWindow {bitlen: ASC_BITLEN}),
+    ];
+    for w in 3..=ASC_BITLEN+1 {
+        rcs.push(Box::new(WNaf {w}));
+    }
+    rcs
+}
+
+/// Pick recoding with the lowest estimated cost for `k`:
+/// * the number of additions first (then depth) for throughput,
+/// * depth first (then the number of additions) for latency
+pub fn pick_recoding(
+    k: u64,
+    objective: ScmObjective,
+) -> Box<dyn ScalarRecoding> {
+    recoding_candidates().into_iter()
+        .min_by_key(|rc| {
+            let (adds, depth) = windows_cost(&rc.windows(k), objective);
+            match objective {
+                ScmObjective::Throughput => (adds, depth),
+                ScmObjective::Latency    => (depth, adds),
+            }
+        })
+        .expect("No recoding candidates.")
+}
@@ -12,7 +12,7 @@ use rayon::prelude::*;
 #[allow(unused_imports)]
 use colored::Colorize;
 
-use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl, ParmEncrWord};
 use super::pbs;
 
 /// Implementation of signum via parallel reduction
@@ -52,6 +52,49 @@ pub fn sgn_impl(
     Ok(ParmCiphertext::single(s_lwe))
 }
 
+/// Implementation of trimming: `X` with `|X| < 2^bits` to (at most) `bits + 1` words
+/// * top trivial zeros are dropped for free
+/// * words from `bits` up sum up to `H ∈ {-1, 0, 1}` (since the lower part is less than `2^bits` in absolute value),
+///   hence they can be replaced by a single word `H = sgn(top words)`
+pub fn trim_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+    bits: usize,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    let mut xt = x.clone();
+    while xt.last().is_some_and(|xi| xi.is_triv_zero()) {
+        xt.pop();
+    }
+    if xt.len() <= bits + 1 {
+        return Ok(xt);
+    }
+
+    let top = xt.split_off(bits);
+    let h = sgn_impl(pc, &top)?;
+    xt.extend(h);
+
+    Ok(xt)
+}
+
+/// Implementation of non-negativity test: `X ≥ 0` (a fresh sample in `{0, 1}`)
+/// * input is expected to be fresh (e.g., a result of addition)
+pub fn nonneg_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+) -> Result<ParmEncrWord, Box<dyn Error>> {
+    // returns one sample .. res in {-15, ..., 15} (to be bootstrapped with nonneg)
+    let s_raw: ParmCiphertext = sgn_recursion_raw(
+        pc,
+        x,
+        true,
+    )?;
+    // bootstrap whether >= 0 .. res in {0, 1}
+    Ok(pbs::nonneg__pi_5(
+        pc,
+        &s_raw[0],
+    ))
+}
+
 /// Internal recursive function:
 ///  - in 1st round, inputs fresh {-1,0,1}
 ///  - in subseq rounds, inputs {-15..15} of qw = 22
@@ -0,0 +1,61 @@
+use std::error::Error;
+
+use crate::*;
+
+#[allow(unused_imports)]
+use colored::Colorize;
+
+use crate::ciphertexts::{ParmCiphertext, ParmCiphertextImpl, ParmEncrWord};
+use crate::userovo::encryption::PARM_CT_MAXLEN;
+use super::{signum, maximum};
+
+
+// =============================================================================
+//
+//  Integer Square Root
+//
+
+/// Implementation of integer square root: `⌊√X⌋` (zero for `X < 0`)
+/// * digit-by-digit (restoring) method: for `i = m-1, ..., 0`, where `m = ⌈n/2⌉`, set `r_i` iff `(R + 2^i)^2 ≤ X`
+/// * remainder `D = X - R^2` is updated alongside: `D' = D - R·2^(i+1) - 2^(2i)` (a single subtraction), which is kept iff `D' ≥ 0`
+/// * for negative `X`, no test passes, hence the result is zero
+/// * n.b., the remainder grows by a word in every step: its value is bounded by `2R`, but redundant digits above may be non-zero
+pub fn isqrt_impl(
+    pc: &ParmesanCloudovo,
+    x:  &ParmCiphertext,
+) -> Result<ParmCiphertext, Box<dyn Error>> {
+    let n = x.len();
+    if n == 0 {
+        return Ok(ParmArithmetics::zero());
+    } else if n >= PARM_CT_MAXLEN {
+        return Err(format!("Square root of {}-word ciphertext not supported (max {} words).", n, PARM_CT_MAXLEN - 1).into());
+    }
+
+    // |X| < 2^n  =>  ⌊√X⌋ < 2^m
+    let m = n.div_ceil(2);
+    let mut r = ParmCiphertext::triv(m, pc);
+    let mut d = x.clone();
+
+    measure_duration!(
+        ["Integer square root ({}-bit, {} steps)", n, m],
+        [
+            for i in (0..m).rev() {
+                // R·2^(i+1) + 2^(2i) .. n.b., only bits above i are set in R, hence word 2i is a (trivial) zero
+                let mut r_sh = ParmArithmetics::shift(pc, &r, i + 1);
+                r_sh[2 * i] = ParmEncrWord::encrypt_word_triv(pc.pub_keys, 1);
+
+                // D' = D - R·2^(i+1) - 2^(2i),  r_i = (D' ≥ 0)
+                let d_new = ParmArithmetics::sub(pc, &d, &r_sh);
+                let ri = signum::nonneg_impl(pc, &d_new)?;
+
+                // remainder is not needed after the last step
+                if i > 0 {
+                    d = maximum::select_impl(pc, &ri, &d_new, &d)?;
+                }
+                r[i] = ri;
+            }
+        ]
+    );
+
+    Ok(r)
+}
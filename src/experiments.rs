@@ -1,4 +1,8 @@
 use std::collections::BTreeMap;
+use std::error::Error;
+use std::time::Instant;
+
+use rand::Rng;
 
 use crate::*;
 use crate::userovo::*;
@@ -55,4 +59,51 @@ pub fn avg_adds_in_scalar_mul() {
     println!("Optim:   {} add's", opt_tot);
     println!("..out of {} scalar mul's ({}-bit window).", val_ads.len(), ASC_BITLEN);
     println!("\n====    Enf of EXPERIMENT    ===================================================\n");
-}
\ No newline at end of file
+}
+
+/// Evaluate the latency of schoolbook multiplication (`mul_schoolbook`, i.e., lengths < 14) w.r.t. mul-ary reduction:
+///  - sequential chain of additions over the mul-ary (original approach), vs.
+///  - parallel tree of additions (`reduce_mulsquary`), as called by `ParmArithmetics::mul`
+/// * depth is given in additions (each of constant bootstrap depth)
+/// * n.b., squaring has no squ-ary of its own: `squ_2_3word` evaluates all words of the result by PBS directly and
+///   `squ_dnq` splits into squarings & a product, hence the former is not affected at all, the latter via its
+///   cross product `x_0·x_1` only
+pub fn mulsquary_reduction_latency(
+    pu: &ParmesanUserovo,
+    pc: &ParmesanCloudovo,
+) -> Result<(), Box<dyn Error>> {
+
+    println!("\n====    EXPERIMENT:  Latency of Schoolbook Multiplication    ===================\n");
+
+    let mut rng = rand::thread_rng();
+
+    println!(" words |  seq. depth   seq. time [ms] |  tree depth  tree time [ms] |  speed-up");
+    for n in [4usize, 8, 12] {
+        let mx: i64 = rng.gen_range(-(1i64 << n) + 1..1i64 << n);
+        let my: i64 = rng.gen_range(-(1i64 << n) + 1..1i64 << n);
+        let x = pu.encrypt(mx, n)?;
+        let y = pu.encrypt(my, n)?;
+
+        // sequential chain
+        let now = Instant::now();
+        let mulary = multiplication::fill_mulary(pc, &x, &y)?;
+        let seq = mulary[1..].iter().fold(mulary[0].clone(), |acc, ri| ParmArithmetics::add(pc, &acc, ri));
+        let seq_ms = now.elapsed().as_secs_f64() * 1e3;
+
+        // parallel tree
+        let now = Instant::now();
+        let tree = ParmArithmetics::mul(pc, &x, &y);
+        let tree_ms = now.elapsed().as_secs_f64() * 1e3;
+
+        if pu.decrypt(&seq)? != mx * my || pu.decrypt(&tree)? != mx * my {
+            return Err(format!("Schoolbook multiplication of {} words differs.", n).into());
+        }
+
+        println!("  {:>4} |  {:>10}  {:>14.1} |  {:>10}  {:>14.1} |  {:>7.2}×",
+            n, n - 1, seq_ms, n.next_power_of_two().trailing_zeros(), tree_ms, seq_ms / tree_ms);
+    }
+
+    println!("\n====    Enf of EXPERIMENT    ===================================================\n");
+
+    Ok(())
+}
@@ -51,7 +51,14 @@ pub use userovo::keys::{PrivKeySet,PubKeySet};
 // Cloudovo modules
 pub mod cloudovo;
 pub use cloudovo::*;
-pub use cloudovo::neural_network::{Perceptron, PercType, NeuralNetwork};
+pub use cloudovo::neural_network::{Perceptron, PercType, Activation, PoolOp, Layer, NeuralNetwork};
+pub use cloudovo::quantization::{FloatPerceptron, FloatNetwork, QuantParams, QuantizedNetwork, QuantReport};
+pub use cloudovo::length_plan::{LayerPlan, LengthPlan};
+pub use cloudovo::private_network::{PrivatePerceptron, PrivateLayer, PrivateNetwork};
+pub use cloudovo::decision_tree::{TreeNode, Forest};
+pub use cloudovo::fixed_point::ParmFixed;
+pub use cloudovo::floating_point::ParmFloat;
+pub use cloudovo::rounding::RoundingMode;
 pub use cloudovo::scalar_multiplication::asc::*;
 
 // Cloudovo modules
@@ -73,12 +80,16 @@ static MIN_QUAD_WEIGHT: usize = 22;
 
 /// Addition-Subtraction Chains' bitlength
 pub static ASC_BITLEN: usize = 12;
-// this file must be present in <exec-dir/assets>, copy it from <lib-root/assets>
-static ASC_12_FILE: &str = "assets/asc-12.yaml";
+// embedded at compile time, i.e., no need to have the file present in <exec-dir/assets>
+static ASC_12_YAML: &str = include_str!("../assets/asc-12.yaml");
+// generated by `scalar_multiplication::asc_search` with `AscObjective::Depth`
+static ASC_12_DEPTH_YAML: &str = include_str!("../assets/asc-12-depth.yaml");
 
 lazy_static::lazy_static! {
 /// Addition-Subtraction Chains for Scalar Multiplication
-pub static ref ASC_12: BTreeMap<usize, Vec<AddShift>> = Asc::map_from_yaml(ASC_BITLEN, ASC_12_FILE).expect("Asc::map_from_yaml failed.");
+pub static ref ASC_12: BTreeMap<usize, Vec<AddShift>> = Asc::map_from_yaml_str(ASC_BITLEN, ASC_12_YAML).expect("Asc::map_from_yaml_str failed.");
+/// Addition-Subtraction Chains for Scalar Multiplication of minimal depth
+pub static ref ASC_12_DEPTH: BTreeMap<usize, Vec<AddShift>> = Asc::map_from_yaml_str(ASC_BITLEN, ASC_12_DEPTH_YAML).expect("Asc::map_from_yaml_str failed.");
 }
 
 /// Keeps log level for nested time measurements
@@ -148,6 +159,63 @@ impl ParmesanUserovo<'_> {
     pub fn decrypt(&self, c: &ParmCiphertext) -> Result<i64, Box<dyn Error>> {   //WISH change to a template for other integer types/lengths, too
         encryption::parm_decrypt(&self.priv_keys, c)
     }
+
+    /// Encrypt a real number as a fixed-point number
+    /// * `m` is rounded to `frac` fractional bits, `words` states the total number of encrypted words
+    pub fn encrypt_fixed(
+        &self,
+        m: f64,
+        frac: usize,
+        words: usize,
+    ) -> Result<ParmFixed, Box<dyn Error>> {
+        let mi = ParmFixed::quantize(m, frac)?;
+        Ok(ParmFixed::new(encryption::parm_encrypt(&self.priv_keys, mi, words)?, frac))
+    }
+
+    /// Decrypt fixed-point number into a real number
+    pub fn decrypt_fixed(&self, c: &ParmFixed) -> Result<f64, Box<dyn Error>> {
+        Ok(ParmFixed::dequantize(encryption::parm_decrypt(&self.priv_keys, &c.ct)?, c.frac))
+    }
+
+    /// Encrypt a real number as a floating-point number
+    /// * mantissa of `prec` bits is encrypted with `prec + 1` words, exponent with `e_words` words
+    pub fn encrypt_float(
+        &self,
+        m: f64,
+        prec: usize,
+        e_words: usize,
+    ) -> Result<ParmFloat, Box<dyn Error>> {
+        let (mi, ei) = ParmFloat::quantize(m, prec)?;
+        if ei.unsigned_abs() >= 1 << e_words {
+            return Err(format!("Exponent {} does not fit {} words.", ei, e_words).into());
+        }
+        Ok(ParmFloat {
+            m: encryption::parm_encrypt(&self.priv_keys, mi, prec + 1)?,
+            e: encryption::parm_encrypt(&self.priv_keys, ei, e_words)?,
+            prec,
+        })
+    }
+
+    /// Decrypt floating-point number into a real number
+    pub fn decrypt_float(&self, c: &ParmFloat) -> Result<f64, Box<dyn Error>> {
+        Ok(ParmFloat::dequantize(
+            encryption::parm_decrypt(&self.priv_keys, &c.m)?,
+            encryption::parm_decrypt(&self.priv_keys, &c.e)?,
+        ))
+    }
+
+    /// Encrypt weights & biases of Neural Network (private model)
+    /// * each parameter is encrypted with `words` words (this will be public)
+    pub fn encrypt_nn(
+        &self,
+        nn: &NeuralNetwork,
+        words: usize,
+    ) -> Result<PrivateNetwork<ParmCiphertext>, Box<dyn Error>> {
+        if words == 0 || words > encryption::PARM_CT_MAXLEN {
+            return Err(format!("Parameters cannot be encrypted with {} words (1..={}).", words, encryption::PARM_CT_MAXLEN).into());
+        }
+        PrivateNetwork::from_nn(nn, words, |v| encryption::parm_encrypt(&self.priv_keys, v, words))
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -184,7 +252,6 @@ impl ParmesanCloudovo<'_> {
 //      - well, for squ it works, for mul?
 //      - in squ, can there be more zeros? we have redundant repre..
 //  - check optimality of squaring (incorrect estimates for longer inputs)
-//  - parallel mulary reduction? is it worth?
 //
 //  - WISH: track quadratic weight within Parmesan Ciphertext (Vec<(LWE, usize)> ??)
 //      - keep track of sample freshness (e.g., in signum recursion that's mess)
@@ -200,14 +267,6 @@ impl ParmesanCloudovo<'_> {
 //  * for squaring of non-power-of-2: multiply |n|n+1|-bit numbers (isn't this too technical? it can be bypassed by adding a triv zero)
 //  * make new estimates on Karatsuba and D&C squaring BS complexity (actually only for 2 and more nested recursion levels, schoolbook does not add extra bits)
 //
-//  * "floating-point-like" feature:
-//      ? at which position shall the number be rounded?
-//      ! it may happen that there is a leading zero
-//      * devise a "conditional shift": bootstrap the leading position in a maximum-like manner
-//      * here, at i-th position, pick either x_i (leading +-1), or x_i-1 (leading 0)
-//          0 1 1 0 1 0 0|1 1   / round and cond. shift
-//      ->  1 1 0 1 0 1
-//
 //  * make lib & bin in single project: https://stackoverflow.com/questions/26946646/rust-package-with-both-a-library-and-a-binary
 //
 //  * cfg for max{} behavior
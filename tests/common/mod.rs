@@ -34,19 +34,41 @@ lazy_static! {
 //
 pub static TESTS_BITLEN_FULL:       usize     =  62;
 pub static TESTS_BITLEN_MAX:        usize     =   7;
+pub static TESTS_N_ARGMAX_MAX:      usize     =   9;
 pub static TESTS_BITLEN_SGN:        usize     =   7;
 pub static TESTS_BITLEN_ROUND:      usize     =   5;
 pub static TESTS_POS_ROUND:         usize     =   7;
 pub static TESTS_BITLEN_MUL:        usize     =   2;
 pub static TESTS_EXTRA_BITLEN_MUL: [usize; 2] =  [8,9];
+pub static TESTS_BITLEN_INNER:      usize     =   6;
+pub static TESTS_N_INNER_MAX:       usize     =   4;
+pub static TESTS_MAT_ROWS:          usize     =   3;
 pub static TESTS_BITLEN_SQU:        usize     =   7;
 pub static TESTS_EXTRA_BITLEN_SQU: [usize; 2] =  [8,9];
 pub static TESTS_BITLEN_ADD:        usize     =   2;
 pub static TESTS_EXTRA_BITLEN_ADD: [usize; 2] = [15,TESTS_BITLEN_FULL-1];
 pub static TESTS_BITLEN_ADD_CONST:  usize     =   8;
+pub static TESTS_BITLEN_SUM:        usize     =   8;
+pub static TESTS_N_SUM_MAX:         usize     =   9;
 pub static TESTS_BITLEN_SCM:        usize     =   9;
 pub static TESTS_BITLEN_SCALAR:     usize     =  30;
-pub static TESTS_BITLEN_KOY_TSU:    usize     =  30;
+pub static TESTS_BITLEN_SCALAR_64:  usize     =  45;
+pub static TESTS_BITLEN_KOY_TSU:    usize     =  62;
+pub static TESTS_BITLEN_ASC_SEARCH: usize     =   8;
+pub static TESTS_BITLEN_DOT_W:      usize     =  16;
+pub static TESTS_N_DOT_MAX:         usize     =   6;
+pub static TESTS_BITLEN_FIXED_INT:  usize     =   4;
+pub static TESTS_FRAC_FIXED:        usize     =   4;
+pub static TESTS_BITLEN_NORM:       usize     =   9;
+pub static TESTS_PREC_FLOAT:        usize     =   6;
+pub static TESTS_E_WORDS_FLOAT:     usize     =   5;
+pub static TESTS_BITLEN_SQRT:       usize     =   8;
+pub static TESTS_BITLEN_POW:        usize     =   3;
+pub static TESTS_POW_MAX_EXP:       u32       =   7;
+pub static TESTS_BITLEN_POLY:       usize     =   3;
+pub static TESTS_POLY_MAX_DEG:      usize     =   5;
+pub static TESTS_BITLEN_POLY_COEF:  usize     =   4;
+pub static TESTS_LEN_POLY_LONG:     usize     =  12;
 pub static TESTS_BITLEN_NNE:        usize     =   5;
 pub static TESTS_EW_MAX_SCALAR:     usize     =   4;
 
@@ -54,6 +76,17 @@ pub static TESTS_EW_MAX_SCALAR:     usize     =   4;
 pub static TESTS_NNE_DEPTH:         usize     =   3;
 pub static TESTS_NNE_LAYER_SIZE:    usize     =   5;
 pub static TESTS_NNE_B_ABS_MAX:     i64       =  15;
+pub static TESTS_NNE_MAX_WORDS:     usize     =  10;
+pub static TESTS_NNE_PARAM_WORDS:   usize     =   5;
+pub static TESTS_NNE_BATCH:         usize     =   5;
+pub static TESTS_QUANT_W_BITS:      usize     =   6;
+pub static TESTS_QUANT_FRAC:        usize     =   5;
+
+// tree-specific
+pub static TESTS_BITLEN_TREE:       usize     =   5;
+pub static TESTS_TREE_DEPTH:        usize     =   3;
+pub static TESTS_TREE_FEATURES:     usize     =   4;
+pub static TESTS_FOREST_SIZE:       usize     =   3;
 
 pub static TESTS_REPEAT_ENCR:       usize     = 100;
 pub static TESTS_REPEAT_ENCR_WORD:  usize     = 100;
@@ -62,12 +95,23 @@ pub static TESTS_REPEAT_MAX:        usize     =   3;
 pub static TESTS_REPEAT_SGN:        usize     =   3;
 pub static TESTS_REPEAT_ROUND:      usize     =  10;
 pub static TESTS_REPEAT_MUL:        usize     =   1;
+pub static TESTS_REPEAT_INNER:      usize     =   2;
 pub static TESTS_REPEAT_SQU:        usize     =   1;
 pub static TESTS_REPEAT_ADD_CONST:  usize     =   3;
 pub static TESTS_REPEAT_ADD_TRIV_0: usize     =   3;
+pub static TESTS_REPEAT_SUM:        usize     =   3;
 pub static TESTS_REPEAT_SCM:        usize     =   3;
 pub static TESTS_REPEAT_KOY_TSU:    usize     =1000;
+pub static TESTS_REPEAT_RECODING:   usize     = 100;
+pub static TESTS_REPEAT_DOT:        usize     =   3;
+pub static TESTS_REPEAT_FIXED:      usize     =   3;
+pub static TESTS_REPEAT_FLOAT:      usize     =   3;
+pub static TESTS_REPEAT_SQRT:       usize     =   2;
+pub static TESTS_REPEAT_POW:        usize     =   2;
+pub static TESTS_REPEAT_POLY:       usize     =   2;
+pub static TESTS_REPEAT_QUANT:      usize     =   4;
 pub static TESTS_REPEAT_NNE:        usize     =   3;
+pub static TESTS_REPEAT_TREE:       usize     =   3;
 
 #[derive(Clone,Copy,PartialEq)]
 pub enum EncrVsTriv {
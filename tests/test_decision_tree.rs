@@ -0,0 +1,137 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::*;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Forest Evaluation over mixed sub-samples vs. plain traversal.
+fn t_forest_eval_some_triv() {
+    for _ in 0..common::TESTS_REPEAT_TREE {
+        let forest = t_gen_forest();
+
+        // generate & encrypt random inputs
+        let mut m_in = vec![];
+        let mut c_in = vec![];
+        for _ in 0..forest.n_features {
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_TREE);
+            m_in.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+            c_in.push(encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV));
+        }
+
+        // homomorphic eval
+        let c_he = forest.eval(&common::TEST_PC, &c_in).expect("Forest::eval failed.");
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+        // plain eval: traversal & oblivious evaluation over i64
+        let m_pl = forest.base + forest.trees.iter().map(|tree| t_traverse(tree, &m_in)).sum::<i64>();
+        assert_eq!(forest.eval(&common::TEST_PC, &m_in).expect("Forest::eval failed."), m_pl);
+
+        println!("  m_in = {:?}\n  forest_eval = {} (exp. {})", m_in, m_he, m_pl);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+#[test]
+/// Decision tree & forest on a hand-computed example (incl. inputs equal to thresholds).
+fn t_tree_example() {
+    //          x0 < 5
+    //         /      \
+    //     x1 < 0      7
+    //     /    \
+    //   -3      2
+    // forest: the tree above, (x1 < 0 ? 1 : -1), base 10
+    let forest_yaml = "n_features: 2\nbase: 10\ntrees:\n- node: split\n  feature: 0\n  threshold: 5\n  left:\n    node: split\n    feature: 1\n    threshold: 0\n    left: {node: leaf, value: -3}\n    right: {node: leaf, value: 2}\n  right: {node: leaf, value: 7}\n- {node: split, feature: 1, threshold: 0, left: {node: leaf, value: 1}, right: {node: leaf, value: -1}}\n";
+    let forest = Forest::from_yaml_str(forest_yaml).expect("Forest::from_yaml_str failed.");
+    assert_eq!(forest.trees[0].depth(), 2);
+
+    for (m_in, exp_tree, exp_forest) in [([4, -1], -3, 8), ([4, 0], 2, 11), ([5, -1], 7, 18), ([-9, 9], 2, 11)] {
+        let c_in: Vec<ParmCiphertext> = m_in.iter().map(|m| common::TEST_PU.encrypt(*m, 5).expect("ParmesanUserovo::encrypt failed.")).collect();
+
+        let m_tree = common::TEST_PU.decrypt(&forest.trees[0].eval(&common::TEST_PC, &c_in).expect("TreeNode::eval failed.")).expect("ParmesanUserovo::decrypt failed.");
+        let m_forest = common::TEST_PU.decrypt(&forest.eval(&common::TEST_PC, &c_in).expect("Forest::eval failed.")).expect("ParmesanUserovo::decrypt failed.");
+        println!("  {:?}: tree = {} (exp. {}), forest = {} (exp. {})", m_in, m_tree, exp_tree, m_forest, exp_forest);
+
+        assert_eq!(forest.trees[0].eval(&common::TEST_PC, &m_in).expect("TreeNode::eval failed."), exp_tree);
+        assert_eq!(m_tree, exp_tree);
+        assert_eq!(m_forest, exp_forest);
+    }
+
+    // single leaf
+    let leaf = TreeNode::Leaf {value: -4};
+    let c_in = vec![common::TEST_PU.encrypt(3, 5).expect("ParmesanUserovo::encrypt failed.")];
+    assert_eq!(common::TEST_PU.decrypt(&leaf.eval(&common::TEST_PC, &c_in).expect("TreeNode::eval failed.")).expect("ParmesanUserovo::decrypt failed."), -4);
+}
+
+#[test]
+/// Forest validation: features out of range, no trees, inputs not matching features.
+fn t_forest_validate() {
+    let split = |feature| TreeNode::Split {feature, threshold: 0, left: Box::new(TreeNode::Leaf {value: 0}), right: Box::new(TreeNode::Leaf {value: 1})};
+
+    assert!(Forest {trees: vec![split(1)], n_features: 2, base: 0}.validate().is_ok());
+    assert!(Forest {trees: vec![split(0), split(2)], n_features: 2, base: 0}.validate().is_err());
+    assert!(Forest {trees: vec![], n_features: 2, base: 0}.validate().is_err());
+    assert!(Forest {trees: vec![split(0)], n_features: 0, base: 0}.validate().is_err());
+    assert!(Forest::from_json_str(r#"{"n_features": 1, "trees": [{"node": "leaf", "value": 3}]}"#).is_ok());
+    assert!(Forest::from_json_str(r#"{"n_features": 1, "trees": [{"node": "split", "feature": 1, "threshold": 0, "left": {"node": "leaf", "value": 0}, "right": {"node": "leaf", "value": 0}}]}"#).is_err());
+
+    // inputs do not match features
+    let forest = Forest {trees: vec![split(1)], n_features: 2, base: 0};
+    assert_eq!(forest.eval(&common::TEST_PC, &[0, 3]).expect("Forest::eval failed."), 1);
+    assert!(forest.eval(&common::TEST_PC, &[0]).is_err());
+    assert!(forest.eval(&common::TEST_PC, &[0, 3, 1]).is_err());
+    assert!(forest.trees[0].eval(&common::TEST_PC, &[0]).is_err());
+    assert!(Forest {trees: vec![split(2)], n_features: 2, base: 0}.eval(&common::TEST_PC, &[0, 3]).is_err());
+}
+
+
+// -----------------------------------------------------------------------------
+//  Plain Traversal & Random Forest Generation
+
+fn t_traverse(node: &TreeNode, m_in: &[i64]) -> i64 {
+    match node {
+        TreeNode::Split {feature, threshold, left, right} => {
+            if m_in[*feature] < *threshold {t_traverse(left, m_in)} else {t_traverse(right, m_in)}
+        },
+        TreeNode::Leaf {value} => *value,
+    }
+}
+
+fn t_gen_tree(depth: usize) -> TreeNode {
+    let mut rng = rand::thread_rng();
+    let t_max = 1i64 << common::TESTS_BITLEN_TREE;
+
+    if depth == 0 || rng.gen_bool(0.2) {
+        TreeNode::Leaf {value: rng.gen_range(-15..=15)}
+    } else {
+        TreeNode::Split {
+            feature: rng.gen_range(0..common::TESTS_TREE_FEATURES),
+            // small thresholds repeat across trees (shared comparisons)
+            threshold: if rng.gen_bool(0.5) {rng.gen_range(-2..=2)} else {rng.gen_range(-t_max..=t_max)},
+            left: Box::new(t_gen_tree(depth - 1)),
+            right: Box::new(t_gen_tree(depth - 1)),
+        }
+    }
+}
+
+fn t_gen_forest() -> Forest {
+    let mut rng = rand::thread_rng();
+
+    let forest = Forest {
+        trees: (0..rng.gen_range(1..=common::TESTS_FOREST_SIZE)).map(|_| t_gen_tree(common::TESTS_TREE_DEPTH)).collect(),
+        n_features: common::TESTS_TREE_FEATURES,
+        base: rng.gen_range(-15..=15),
+    };
+    forest.validate().expect("Forest::validate failed.");
+    forest
+}
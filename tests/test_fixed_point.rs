@@ -0,0 +1,130 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::arithmetics::ParmArithmetics;
+use parmesan::ParmFixed;
+
+#[allow(dead_code)]
+mod common;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Fixed-point encryption & decryption.
+fn t_fixed_encr_decr() {
+    for _ in 0..common::TESTS_REPEAT_FIXED {
+        let (m, mi) = gen_rand_fixed(common::TESTS_FRAC_FIXED);
+        let c = common::TEST_PU.encrypt_fixed(m, common::TESTS_FRAC_FIXED, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+        let m_he = common::TEST_PU.decrypt_fixed(&c).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        println!("  m = {} (~ {}), decr = {}", m, mi, m_he);
+
+        assert_eq!(m_he, ParmFixed::dequantize(mi, common::TESTS_FRAC_FIXED));
+    }
+}
+
+#[test]
+/// Quantization with a binary-point position beyond 64 bits.
+fn t_fixed_quantize_large_frac() {
+    assert_eq!(ParmFixed::quantize(0.0, 70).expect("ParmFixed::quantize failed."), 0);
+    assert!(ParmFixed::quantize(3.0, 64).is_err());
+    assert_eq!(ParmFixed::dequantize(1, 64), 2f64.powi(-64));
+    assert_eq!(ParmFixed::dequantize(-3, 70), -3.0 * 2f64.powi(-70));
+}
+
+#[test]
+/// Fixed-point addition & subtraction of different binary-point positions.
+fn t_fixed_add_sub() {
+    for _ in 0..common::TESTS_REPEAT_FIXED {
+        let fx = common::TESTS_FRAC_FIXED;
+        let fy = common::TESTS_FRAC_FIXED / 2;
+        let (x, xi) = gen_rand_fixed(fx);
+        let (y, yi) = gen_rand_fixed(fy);
+        let cx = common::TEST_PU.encrypt_fixed(x, fx, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+        let cy = common::TEST_PU.encrypt_fixed(y, fy, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+
+        let c_add = ParmFixed::add(&common::TEST_PC, &cx, &cy);
+        let c_sub = ParmFixed::sub(&common::TEST_PC, &cx, &cy);
+        let m_add = common::TEST_PU.decrypt_fixed(&c_add).expect("ParmesanUserovo::decrypt_fixed failed.");
+        let m_sub = common::TEST_PU.decrypt_fixed(&c_sub).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        // plain eval (exact, since aligned to the finer position)
+        let xd = ParmFixed::dequantize(xi, fx);
+        let yd = ParmFixed::dequantize(yi, fy);
+
+        println!("  x = {}, y = {}\n  add = {} (exp. {}), sub = {} (exp. {})", xd, yd, m_add, xd + yd, m_sub, xd - yd);
+
+        assert_eq!(c_add.frac, fx);
+        assert_eq!(m_add, xd + yd);
+        assert_eq!(m_sub, xd - yd);
+    }
+}
+
+#[test]
+/// Fixed-point multiplication with rescale.
+fn t_fixed_mul() {
+    for _ in 0..common::TESTS_REPEAT_FIXED {
+        let f = common::TESTS_FRAC_FIXED;
+        let (x, xi) = gen_rand_fixed(f);
+        let (y, yi) = gen_rand_fixed(f);
+        let cx = common::TEST_PU.encrypt_fixed(x, f, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+        let cy = common::TEST_PU.encrypt_fixed(y, f, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+
+        let c_he = ParmFixed::mul(&common::TEST_PC, &cx, &cy, f);
+        let m_he = common::TEST_PU.decrypt_fixed(&c_he).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        // plain eval: product with 2f fractional bits, rounded at f
+        let m_pl = ParmFixed::dequantize(ParmArithmetics::round_at(&common::TEST_PC, &(xi * yi), f) >> f, f);
+
+        println!("  x = {}, y = {}\n  mul = {} (exp. {})", x, y, m_he, m_pl);
+
+        assert_eq!(c_he.frac, f);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+#[test]
+/// Fixed-point scalar multiplication by a fractional constant.
+fn t_fixed_scalar_mul() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_FIXED {
+        let f = common::TESTS_FRAC_FIXED;
+        let (x, xi) = gen_rand_fixed(f);
+        let k: f64 = rng.gen_range(-100.0..100.0);
+        let cx = common::TEST_PU.encrypt_fixed(x, f, fixed_words()).expect("ParmesanUserovo::encrypt_fixed failed.");
+
+        let c_he = ParmFixed::scalar_mul(&common::TEST_PC, k, f, &cx).expect("ParmFixed::scalar_mul failed.");
+        let m_he = common::TEST_PU.decrypt_fixed(&c_he).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        // plain eval
+        let ki = ParmFixed::quantize(k, f).expect("ParmFixed::quantize failed.");
+        let m_pl = ParmFixed::dequantize(ParmArithmetics::round_at(&common::TEST_PC, &(ki * xi), f) >> f, f);
+
+        println!("  x = {}, k = {}\n  scm = {} (exp. {})", x, k, m_he, m_pl);
+
+        assert_eq!(c_he.frac, f);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Auxiliary Functions
+
+/// Number of words of fixed-point test values
+fn fixed_words() -> usize {
+    common::TESTS_BITLEN_FIXED_INT + common::TESTS_FRAC_FIXED + 1
+}
+
+/// Random real number (within integer bit-length) & its quantized value
+fn gen_rand_fixed(frac: usize) -> (f64, i64) {
+    let mut rng = rand::thread_rng();
+    let bound = (1 << common::TESTS_BITLEN_FIXED_INT) as f64;
+    let m: f64 = rng.gen_range(-bound..bound);
+    (m, ParmFixed::quantize(m, frac).expect("ParmFixed::quantize failed."))
+}
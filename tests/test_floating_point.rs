@@ -0,0 +1,162 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::cloudovo::floating_point;
+use parmesan::ParmFloat;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Normalization of encrypted sub-samples only.
+fn t_normalize_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_normalize_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Normalization of trivial sub-samples only.
+fn t_normalize_all_triv() {
+    println!("All-Triv ...");
+    t_impl_normalize_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Normalization of mixed sub-samples.
+fn t_normalize_some_triv() {
+    println!("Mixed ...");
+    t_impl_normalize_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Floating-point encryption & decryption.
+fn t_float_encr_decr() {
+    for _ in 0..common::TESTS_REPEAT_FLOAT {
+        let x = gen_rand_float();
+        let c = encrypt_float(x);
+        let x_he = common::TEST_PU.decrypt_float(&c).expect("ParmesanUserovo::decrypt_float failed.");
+
+        println!("  x = {}, decr = {}", x, x_he);
+
+        assert!((x_he - x).abs() <= x.abs() / (1u64 << common::TESTS_PREC_FLOAT) as f64);
+    }
+}
+
+#[test]
+/// Floating-point addition & subtraction.
+fn t_float_add_sub() {
+    for _ in 0..common::TESTS_REPEAT_FLOAT {
+        let (x, y) = (gen_rand_float(), gen_rand_float());
+        let (cx, cy) = (encrypt_float(x), encrypt_float(y));
+        let (xq, yq) = (decrypt_float(&cx), decrypt_float(&cy));
+
+        let c_add = ParmFloat::add(&common::TEST_PC, &cx, &cy).expect("ParmFloat::add failed.");
+        let c_sub = ParmFloat::sub(&common::TEST_PC, &cx, &cy).expect("ParmFloat::sub failed.");
+        let (m_add, m_sub) = (decrypt_float(&c_add), decrypt_float(&c_sub));
+
+        // alignment & rounding errors are relative to the greater operand
+        let tol = f64::max(xq.abs(), yq.abs()) / (1u64 << (common::TESTS_PREC_FLOAT - 2)) as f64;
+
+        println!("  x = {}, y = {}\n  add = {} (exp. {}), sub = {} (exp. {})", xq, yq, m_add, xq + yq, m_sub, xq - yq);
+
+        assert!((m_add - (xq + yq)).abs() <= tol);
+        assert!((m_sub - (xq - yq)).abs() <= tol);
+    }
+}
+
+#[test]
+/// Floating-point multiplication.
+fn t_float_mul() {
+    for _ in 0..common::TESTS_REPEAT_FLOAT {
+        let (x, y) = (gen_rand_float(), gen_rand_float());
+        let (cx, cy) = (encrypt_float(x), encrypt_float(y));
+        let (xq, yq) = (decrypt_float(&cx), decrypt_float(&cy));
+
+        let c_he = ParmFloat::mul(&common::TEST_PC, &cx, &cy).expect("ParmFloat::mul failed.");
+        let m_he = decrypt_float(&c_he);
+
+        println!("  x = {}, y = {}\n  mul = {} (exp. {})", xq, yq, m_he, xq * yq);
+
+        assert!((m_he - xq * yq).abs() <= (xq * yq).abs() / (1u64 << (common::TESTS_PREC_FLOAT - 1)) as f64);
+    }
+}
+
+#[test]
+/// Floating-point comparison.
+fn t_float_cmp() {
+    for _ in 0..common::TESTS_REPEAT_FLOAT {
+        let (x, y) = (gen_rand_float(), gen_rand_float());
+        let (cx, cy) = (encrypt_float(x), encrypt_float(y));
+        let (xq, yq) = (decrypt_float(&cx), decrypt_float(&cy));
+
+        let s_xy = common::TEST_PU.decrypt(&ParmFloat::cmp(&common::TEST_PC, &cx, &cy).expect("ParmFloat::cmp failed.")).expect("ParmesanUserovo::decrypt failed.");
+        let s_xx = common::TEST_PU.decrypt(&ParmFloat::cmp(&common::TEST_PC, &cx, &cx).expect("ParmFloat::cmp failed.")).expect("ParmesanUserovo::decrypt failed.");
+
+        println!("  x = {}, y = {}\n  cmp(x,y) = {}, cmp(x,x) = {}", xq, yq, s_xy, s_xx);
+
+        // n.b., close values might be rounded to equal
+        let tol = f64::max(xq.abs(), yq.abs()) / (1u64 << (common::TESTS_PREC_FLOAT - 2)) as f64;
+        if (xq - yq).abs() > tol {
+            assert_eq!(s_xy, if xq > yq {1} else {-1});
+        }
+        assert_eq!(s_xx, 0);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Test Implementations
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_normalize_with_mode(mode: EncrVsTriv) {
+    for _ in 0..common::TESTS_REPEAT_FLOAT {
+        for bl in 1..=common::TESTS_BITLEN_NORM {
+            // generate random vector, convert to integer & encrypt
+            let m_vec = gen_rand_vec(bl);
+            let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+            let c = encrypt_with_mode(&m_vec, mode);
+
+            // normalize -> decrypt
+            let (cm, ce) = floating_point::normalize_impl(&common::TEST_PC, &c).expect("normalize_impl failed.");
+            let mm = common::TEST_PU.decrypt(&cm).expect("ParmesanUserovo::decrypt failed.");
+            let me = common::TEST_PU.decrypt(&ce).expect("ParmesanUserovo::decrypt failed.");
+
+            println!("  m = {} ({}-bit: {:?})\n  norm = {} · 2^{}", m, bl, m_vec, mm, me);
+
+            // exact & normalized
+            assert!(me <= 0);
+            assert_eq!(mm, m << (-me));
+            if m != 0 {
+                assert!(mm.abs() >= 1 << (bl - 1));
+                assert!(mm.abs() < 1 << bl);
+            }
+        }
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Auxiliary Functions
+
+/// Random real number of random magnitude
+fn gen_rand_float() -> f64 {
+    let mut rng = rand::thread_rng();
+    let x: f64 = rng.gen_range(0.5..1.0) * 2f64.powi(rng.gen_range(-6..=6));
+    if rng.gen::<bool>() {x} else {-x}
+}
+
+fn encrypt_float(x: f64) -> ParmFloat {
+    common::TEST_PU.encrypt_float(x, common::TESTS_PREC_FLOAT, common::TESTS_E_WORDS_FLOAT).expect("ParmesanUserovo::encrypt_float failed.")
+}
+
+fn decrypt_float(c: &ParmFloat) -> f64 {
+    common::TEST_PU.decrypt_float(c).expect("ParmesanUserovo::decrypt_float failed.")
+}
@@ -0,0 +1,128 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::arithmetics::ParmArithmetics;
+use parmesan::ciphertexts::ParmCiphertext;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Inner product of encrypted sub-samples only.
+fn t_inner_prod_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_inner_prod_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Inner product of trivial sub-samples only.
+fn t_inner_prod_all_triv() {
+    println!("All-Triv ...");
+    t_impl_inner_prod_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Inner product of mixed sub-samples.
+fn t_inner_prod_some_triv() {
+    println!("Mixed ...");
+    t_impl_inner_prod_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Matrix-vector product of mixed sub-samples.
+fn t_mat_vec_some_triv() {
+    println!("Mixed ...");
+    t_impl_mat_vec_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+
+// -----------------------------------------------------------------------------
+//  Test Implementations
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_inner_prod_with_mode(mode: EncrVsTriv) {
+    for n in 1..=common::TESTS_N_INNER_MAX {
+        for _ in 0..common::TESTS_REPEAT_INNER {
+            // generate random vectors (of random lengths)
+            let (mx, cx) = t_gen_vec_with_mode(n, mode);
+            let (my, cy) = t_gen_vec_with_mode(n, mode);
+
+            println!("  mx = {:?}\n  my = {:?}", mx, my);
+
+            // homomorphic eval -> decrypt
+            let c_he = ParmArithmetics::inner_prod(&common::TEST_PC, &cx, &cy);
+            let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+            // plain eval
+            let m_pl = ParmArithmetics::inner_prod(&common::TEST_PC, &mx, &my);
+
+            println!("  inner prod = {} (exp. {})", m_he, m_pl);
+
+            // compare results
+            assert_eq!(m_he, m_pl);
+        }
+    }
+}
+
+/// Implementation of matrix-vector product test
+fn t_impl_mat_vec_with_mode(mode: EncrVsTriv) {
+    let n = common::TESTS_N_INNER_MAX;
+
+    for _ in 0..common::TESTS_REPEAT_INNER {
+        // generate random matrix (row-wise) & vector
+        let mut mm: Vec<Vec<i64>> = Vec::new();
+        let mut cm_rows: Vec<Vec<ParmCiphertext>> = Vec::new();
+        for _ in 0..common::TESTS_MAT_ROWS {
+            let (mi, ci) = t_gen_vec_with_mode(n, mode);
+            mm.push(mi);
+            cm_rows.push(ci);
+        }
+        let (mx, cx) = t_gen_vec_with_mode(n, mode);
+
+        println!("  M = {:?}\n  x = {:?}", mm, mx);
+
+        // homomorphic eval -> decrypt
+        let c_he = ParmArithmetics::mat_vec(&common::TEST_PC, &cm_rows, &cx);
+        let m_he: Vec<i64> = c_he.iter().map(|ci| common::TEST_PU.decrypt(ci).expect("ParmesanUserovo::decrypt failed.")).collect();
+
+        // plain eval
+        let m_pl = ParmArithmetics::mat_vec(&common::TEST_PC, &mm, &mx);
+
+        println!("  M × x = {:?} (exp. {:?})", m_he, m_pl);
+
+        // compare results
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Aux Functions
+
+/// Generation of a vector of n random integers (of random bit-lengths) & their encryptions
+fn t_gen_vec_with_mode(
+    n: usize,
+    mode: EncrVsTriv,
+) -> (Vec<i64>, Vec<ParmCiphertext>) {
+    let mut rng = rand::thread_rng();
+
+    let mut ms: Vec<i64> = Vec::new();
+    let mut cs: Vec<ParmCiphertext> = Vec::new();
+
+    for _ in 0..n {
+        let bl = rng.gen_range(0..=common::TESTS_BITLEN_INNER);
+        let m_vec = gen_rand_vec(bl);
+        ms.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+        cs.push(encrypt_with_mode(&m_vec, mode));
+    }
+
+    (ms, cs)
+}
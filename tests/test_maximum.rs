@@ -57,6 +57,44 @@ fn t_max_some_triv_difflen() {
     t_impl_max_with_mode(EncrVsTriv::ENCRTRIV, false);
 }
 
+#[test]
+/// Arg-max of mixed sub-samples, different lengths & counts (incl. ties).
+fn t_argmax_some_triv() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_MAX {
+        // short values make ties likely
+        let n = rng.gen_range(1..=common::TESTS_N_ARGMAX_MAX);
+        let ms_vec: Vec<Vec<i32>> = (0..n).map(|_| gen_rand_vec(rng.gen_range(0..=3))).collect();
+        let ms: Vec<i64> = ms_vec.iter().map(|mi| encryption::convert_from_vec(mi).expect("convert failed.")).collect();
+        let cs: Vec<ParmCiphertext> = ms_vec.iter().map(|mi| encrypt_with_mode(mi, EncrVsTriv::ENCRTRIV)).collect();
+
+        println!("  ms = {:?}", ms);
+
+        let c_he = ParmArithmetics::argmax(&common::TEST_PC, &cs);
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+        let m_pl = ParmArithmetics::argmax(&common::TEST_PC, &ms);
+
+        println!("  argmax = {} (exp. {})", m_he, m_pl);
+
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+#[test]
+/// Arg-max: the first maximum wins.
+fn t_argmax_ties() {
+    for (ms, exp) in [(vec![5], 0), (vec![-2, -2, -2], 0), (vec![1, 3, -4, 3, 2], 1), (vec![0, -1, 0, 7, 7, 7, 7, 7, 7], 3)] {
+        let cs: Vec<ParmCiphertext> = ms.iter().map(|mi| common::TEST_PU.encrypt(*mi, 4).expect("ParmesanUserovo::encrypt failed.")).collect();
+        let m_he = common::TEST_PU.decrypt(&ParmArithmetics::argmax(&common::TEST_PC, &cs)).expect("ParmesanUserovo::decrypt failed.");
+
+        println!("  argmax {:?} = {} (exp. {})", ms, m_he, exp);
+
+        assert_eq!(ParmArithmetics::argmax(&common::TEST_PC, &ms), exp);
+        assert_eq!(m_he, exp);
+    }
+}
+
 // Special
 
 #[test]
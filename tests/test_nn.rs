@@ -18,21 +18,328 @@ use common::*;
 /// NN Evaluation over encrypted sub-samples only.
 fn t_nn_eval_non_triv() {
     println!("Non-Triv ...");
-    t_impl_nn_eval_with_mode(EncrVsTriv::ENCR);
+    t_impl_nn_eval_with_mode(EncrVsTriv::ENCR, t_gen_nn);
 }
 
 #[test]
 /// NN Evaluation over trivial sub-samples only.
 fn t_nn_eval_all_triv() {
     println!("All-Triv ...");
-    t_impl_nn_eval_with_mode(EncrVsTriv::TRIV);
+    t_impl_nn_eval_with_mode(EncrVsTriv::TRIV, t_gen_nn);
 }
 
 #[test]
 /// NN Evaluation over mixed sub-samples.
 fn t_nn_eval_some_triv() {
     println!("Mixed ...");
-    t_impl_nn_eval_with_mode(EncrVsTriv::ENCRTRIV);
+    t_impl_nn_eval_with_mode(EncrVsTriv::ENCRTRIV, t_gen_nn);
+}
+
+#[test]
+/// Convolutional NN Evaluation over mixed sub-samples.
+fn t_cnn_eval_some_triv() {
+    println!("CNN Mixed ...");
+    t_impl_nn_eval_with_mode(EncrVsTriv::ENCRTRIV, t_gen_cnn);
+}
+
+#[test]
+/// Convolution & pooling on a hand-computed example.
+fn t_cnn_example() {
+    // 3×3 input -> 2×2 conv (diagonal, stride 1, no padding) -> 2×2 pooling
+    //   6  8        ACT, b = -7:  0 1        Sum: 13
+    //  12 14                      5 7        Avg:  3 (= round(13/4))
+    let yaml_str = "n_inputs: 9\nlayers:\n- layer: conv2d\n  in_channels: 1\n  in_width: 3\n  kernel_size: 2\n  stride: 1\n  padding: 0\n  kernels:\n  - {t: ACT, w: [1, 0, 0, 1], b: -7}\n- layer: pool2d\n  op: sum\n  in_channels: 1\n  in_width: 2\n  size: 2\n  stride: 2\n- layer: flatten\n";
+    let m_in: Vec<i64> = (1..=9).collect();
+    let c_in: Vec<ParmCiphertext> = m_in.iter().map(|m| common::TEST_PU.encrypt(*m, 5).expect("ParmesanUserovo::encrypt failed.")).collect();
+
+    for (op, exp) in [("sum", 13), ("avg", 3), ("max", 7)] {
+        let nn = NeuralNetwork::from_yaml_str(&yaml_str.replace("op: sum", &format!("op: {}", op))).expect("NeuralNetwork::from_yaml_str failed.");
+        assert_eq!(nn.eval(&common::TEST_PC, &m_in), vec![exp]);
+
+        let c_he = nn.eval(&common::TEST_PC, &c_in);
+        let m_he: Vec<i64> = c_he.iter().map(|co| common::TEST_PU.decrypt(co).expect("ParmesanUserovo::decrypt failed.")).collect();
+        println!("  {} pooling: {:?} (exp. [{}])", op, m_he, exp);
+        assert_eq!(m_he, vec![exp]);
+    }
+
+    // 1D: padding contributes zeros, stride 2
+    //  [0] 1 2 3 4 [0]  ->  (0 - 1), (2 - 3), (4 - 0)
+    let nn = NeuralNetwork::from_json_str(r#"{"n_inputs": 4, "layers": [{"layer": "conv1d", "in_channels": 1, "kernel_size": 2, "stride": 2, "padding": 1, "kernels": [{"t": "LIN", "w": [1, -1], "b": 0}]}]}"#).expect("NeuralNetwork::from_json_str failed.");
+    assert_eq!(nn.eval(&common::TEST_PC, &m_in[..4].to_vec()), vec![-1, -1, 4]);
+}
+
+#[test]
+/// Perceptrons of a layer are evaluated concurrently, the output order must be kept.
+fn t_nn_layer_order() {
+    // i-th perceptron: i·x (+ 1 if ACT with identity)
+    let n = 4 * common::TESTS_NNE_LAYER_SIZE;
+    let nn = NeuralNetwork {
+        layers: vec![Layer::Dense {perceptrons: (0..n).map(|i| Perceptron {
+            t: if i % 2 == 0 {PercType::LIN} else {PercType::ACT},
+            w: vec![i as i32],
+            b: (i % 2) as i64,
+            a: Activation::Identity,
+        }).collect()}],
+        n_inputs: 1,
+        argmax: false,
+    };
+
+    let m_vec = gen_rand_vec(common::TESTS_BITLEN_NNE);
+    let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+    let c = encrypt_with_mode(&m_vec, EncrVsTriv::ENCR);
+    let exp: Vec<i64> = (0..n as i64).map(|i| i * m + i % 2).collect();
+
+    let m_he: Vec<i64> = nn.eval(&common::TEST_PC, &vec![c]).iter().map(|co| common::TEST_PU.decrypt(co).expect("ParmesanUserovo::decrypt failed.")).collect();
+    println!("  layer = {:?}\n  (exp. {:?})", m_he, exp);
+    assert_eq!(nn.eval(&common::TEST_PC, &vec![m]), exp);
+    assert_eq!(m_he, exp);
+}
+
+#[test]
+/// Batched NN Evaluation: streamed results match single evaluations, for various chunk sizes.
+fn t_nn_eval_batch() {
+    let nn = t_gen_nn();
+
+    let mut m_in = vec![];
+    let mut c_in = vec![];
+    for _ in 0..common::TESTS_NNE_BATCH {
+        let m_vec: Vec<Vec<i32>> = (0..nn.n_inputs).map(|_| gen_rand_vec(common::TESTS_BITLEN_NNE)).collect();
+        m_in.push(m_vec.iter().map(|mi| encryption::convert_from_vec(mi).expect("convert failed.")).collect::<Vec<i64>>());
+        c_in.push(m_vec.iter().map(|mi| encrypt_with_mode(mi, EncrVsTriv::ENCRTRIV)).collect::<Vec<ParmCiphertext>>());
+    }
+    let exp: Vec<Vec<i64>> = m_in.iter().map(|mi| nn.eval(&common::TEST_PC, mi)).collect();
+
+    for chunk_size in [0, 1, 2] {
+        let m_he = std::sync::Mutex::new(vec![None; common::TESTS_NNE_BATCH]);
+        nn.eval_batch(&common::TEST_PC, &c_in, chunk_size, |i, co: Vec<ParmCiphertext>| {
+            let mo: Vec<i64> = co.iter().map(|ci| common::TEST_PU.decrypt(ci).expect("ParmesanUserovo::decrypt failed.")).collect();
+            let prev = m_he.lock().expect("Mutex::lock failed.")[i].replace(mo);
+            assert!(prev.is_none());
+        });
+        let m_he: Vec<Vec<i64>> = m_he.into_inner().expect("Mutex::into_inner failed.").into_iter().map(|mo| mo.expect("missing result.")).collect();
+
+        let m_pl = std::sync::Mutex::new(vec![vec![]; common::TESTS_NNE_BATCH]);
+        nn.eval_batch(&common::TEST_PC, &m_in, chunk_size, |i, mo| m_pl.lock().expect("Mutex::lock failed.")[i] = mo);
+
+        println!("  batch (chunks of {}) = {:?}\n  (exp. {:?})", chunk_size, m_he, exp);
+        assert_eq!(m_pl.into_inner().expect("Mutex::into_inner failed."), exp);
+        assert_eq!(m_he, exp);
+    }
+}
+
+#[test]
+/// NN Evaluation with arg-max output: the index of the maximal output only.
+fn t_nn_argmax() {
+    for _ in 0..common::TESTS_REPEAT_NNE {
+        let mut nn = t_gen_nn();
+
+        let mut m_in = vec![];
+        let mut c_in = vec![];
+        for _ in 0..nn.n_inputs {
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_NNE);
+            m_in.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+            c_in.push(encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV));
+        }
+        let m_out = nn.eval(&common::TEST_PC, &m_in);
+        let m_max = m_out.iter().max().expect("max failed.");
+        let exp = m_out.iter().position(|mi| mi == m_max).expect("position failed.") as i64;
+
+        nn.argmax = true;
+        let c_he = nn.eval(&common::TEST_PC, &c_in);
+        assert_eq!(c_he.len(), 1);
+        let m_he = common::TEST_PU.decrypt(&c_he[0]).expect("ParmesanUserovo::decrypt failed.");
+
+        println!("  outputs = {:?}, argmax = {} (exp. {})", m_out, m_he, exp);
+        assert_eq!(nn.eval(&common::TEST_PC, &m_in), vec![exp]);
+        assert_eq!(m_he, exp);
+    }
+
+    // option defaults to all outputs
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 1\nlayers:\n- layer: rescale\n  pos: 0\n").expect("NeuralNetwork::from_yaml_str failed.");
+    assert!(!nn.argmax);
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 1\nargmax: true\nlayers:\n- layer: rescale\n  pos: 0\n").expect("NeuralNetwork::from_yaml_str failed.");
+    assert!(nn.argmax);
+}
+
+#[test]
+/// Length planning: bounds, trimming & rounding within word budget.
+fn t_nn_length_plan() {
+    let m_max = (1i64 << common::TESTS_BITLEN_NNE) - 1;
+
+    for _ in 0..common::TESTS_REPEAT_NNE {
+        let mut nn = t_gen_nn();
+        // sign does not scale, clamping needs spare fractional bits (cf. LengthPlan::new)
+        for layer in nn.layers.iter_mut() {
+            if let Layer::Dense {perceptrons} = layer {
+                for perc in perceptrons.iter_mut() {
+                    perc.a = match perc.a {
+                        Activation::Sign                => Activation::Relu,
+                        Activation::HardTanh {frac}     => Activation::HardTanh {frac: frac + common::TESTS_NNE_MAX_WORDS},
+                        Activation::HardSigmoid {frac}  => Activation::HardSigmoid {frac: frac + common::TESTS_NNE_MAX_WORDS},
+                        a => a,
+                    };
+                }
+            }
+        }
+
+        for max_words in [None, Some(common::TESTS_NNE_MAX_WORDS)] {
+            let plan = LengthPlan::new(&nn, (-m_max, m_max), max_words).expect("LengthPlan::new failed.");
+            println!("{}", plan);
+            match max_words {
+                None     => assert_eq!(plan.nn, nn),
+                Some(mw) => assert!(plan.words() <= mw),
+            }
+
+            // random inputs
+            let mut m_in = vec![];
+            let mut c_in = vec![];
+            for _ in 0..nn.n_inputs {
+                let m_vec = gen_rand_vec(common::TESTS_BITLEN_NNE);
+                m_in.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+                c_in.push(encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV));
+            }
+
+            // trimming keeps values
+            let c_he = plan.eval(&common::TEST_PC, &c_in);
+            let m_pl = plan.nn.eval(&common::TEST_PC, &m_in);
+            assert_eq!(plan.eval(&common::TEST_PC, &m_in), m_pl);
+
+            let lp = plan.layers.last().expect("last failed.");
+            for (((co, mp), (lo, hi)), bits) in c_he.iter().zip(m_pl.iter()).zip(lp.out_bounds.iter()).zip(lp.out_bits.iter()) {
+                let m = common::TEST_PU.decrypt(co).expect("ParmesanUserovo::decrypt failed.");
+                println!("  {} in [{}, {}], {} words (exp. {}, max {})", m, lo, hi, co.len(), mp, bits + 1);
+                assert_eq!(m, *mp);
+                assert!(*lo <= m && m <= *hi);
+                assert!(co.len() <= bits + 1);
+            }
+        }
+    }
+
+    // hand-computed: 3·x0 - 2·x1 + 1 in [-19, 21] (5 bits) .. rounding by 1 bit gives [-9, 11] (4 bits)
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 2\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: LIN, w: [3, -2], b: 1}\n").expect("NeuralNetwork::from_yaml_str failed.");
+    let plan = LengthPlan::new(&nn, (-4, 4), None).expect("LengthPlan::new failed.");
+    assert_eq!((plan.in_bits, plan.layers[0].out_bounds.clone(), plan.words()), (3, vec![(-19, 21)], 6));
+    let plan = LengthPlan::new(&nn, (-4, 4), Some(5)).expect("LengthPlan::new failed.");
+    println!("{}", plan);
+    assert_eq!(plan.nn.layers[0], Layer::Rescale {pos: 1});
+    assert_eq!(plan.layers[1].out_bounds, vec![(-9, 11)]);
+    assert_eq!((plan.out_shift, plan.words()), (1, 5));
+    // inputs over budget, empty range
+    assert!(LengthPlan::new(&nn, (-4, 4), Some(3)).is_err());
+    assert!(LengthPlan::new(&nn, (4, -4), None).is_err());
+}
+
+#[test]
+/// Activation functions: encrypted vs. plain vs. closed form.
+fn t_nn_activations() {
+    // half-up rounding of x / 2^s
+    let rnd = |x: i64, s: usize| (x + (1 << (s - 1))) >> s;
+
+    for _ in 0..common::TESTS_REPEAT_NNE {
+        let m_vec = gen_rand_vec(common::TESTS_BITLEN_NNE);
+        let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+        let c = encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV);
+
+        for (a, exp) in [
+            (Activation::Sign,                      m.signum()),
+            (Activation::Relu,                      m.max(0)),
+            (Activation::LeakyRelu {shift: 2},      m.max(rnd(m, 2))),
+            (Activation::HardTanh {frac: 2},        m.clamp(-4, 4)),
+            (Activation::HardSigmoid {frac: 2},     (rnd(m, 2) + 2).clamp(0, 4)),
+            (Activation::Identity,                  m),
+        ] {
+            let m_pl = a.eval(&common::TEST_PC, &m);
+            let m_he = common::TEST_PU.decrypt(&a.eval(&common::TEST_PC, &c)).expect("ParmesanUserovo::decrypt failed.");
+
+            println!("  {:?}({}) = {} (exp. {}, plain {})", a, m, m_he, exp, m_pl);
+            assert_eq!(m_pl, exp);
+            assert_eq!(m_he, exp);
+        }
+    }
+
+    // omitted activation is ReLU, bad parameters
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 1\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: ACT, w: [1], b: 0}\n").expect("NeuralNetwork::from_yaml_str failed.");
+    assert_eq!(nn.eval(&common::TEST_PC, &vec![-3]), vec![0]);
+    if let Layer::Dense {perceptrons} = &nn.layers[0] {assert_eq!(perceptrons[0].a, Activation::Relu);}
+    assert!(NeuralNetwork::from_yaml_str("n_inputs: 1\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: ACT, w: [1], b: 0, a: {act: leaky_relu, shift: 0}}\n").is_err());
+    assert!(NeuralNetwork::from_yaml_str("n_inputs: 1\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: ACT, w: [1], b: 0, a: {act: hard_sigmoid, frac: 0}}\n").is_err());
+    assert!(NeuralNetwork::from_yaml_str("n_inputs: 1\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: ACT, w: [1], b: 0, a: {act: sign}}\n").is_ok());
+}
+
+#[test]
+/// NN (de)serialization: JSON, YAML & file round-trip.
+fn t_nn_serde() {
+    for _ in 0..common::TESTS_REPEAT_NNE {
+        let nn = t_gen_nn();
+        nn.validate().expect("NeuralNetwork::validate failed.");
+
+        let json_str = nn.to_json_string().expect("NeuralNetwork::to_json_string failed.");
+        assert_eq!(NeuralNetwork::from_json_str(&json_str).expect("NeuralNetwork::from_json_str failed."), nn);
+
+        let yaml_str = nn.to_yaml_string().expect("NeuralNetwork::to_yaml_string failed.");
+        assert_eq!(NeuralNetwork::from_yaml_str(&yaml_str).expect("NeuralNetwork::from_yaml_str failed."), nn);
+
+        for ext in ["json", "yaml", "yml"] {
+            let filename = std::env::temp_dir().join(format!("parmesan-test-nn-{}.{}", std::process::id(), ext));
+            let filename = filename.to_str().expect("to_str failed.");
+            nn.save_to_file(filename).expect("NeuralNetwork::save_to_file failed.");
+            let nn_loaded = NeuralNetwork::from_file(filename).expect("NeuralNetwork::from_file failed.");
+            fs::remove_file(filename).expect("remove_file failed.");
+            assert_eq!(nn_loaded, nn);
+        }
+    }
+
+    // embedded demo networks
+    assert_eq!(demo_nn().n_inputs, 3);
+    assert_eq!(arrhythmia_nn().n_inputs, 16);
+}
+
+#[test]
+/// NN evaluation with more/fewer inputs than weights: missing weights count as zero, extra weights are ignored.
+fn t_nn_eval_mis_sized() {
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 2\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: LIN, w: [3, -2], b: 1}\n  - {t: MAX, w: [1, 2], b: 0}\n").expect("NeuralNetwork::from_yaml_str failed.");
+
+    for (m_in, exp) in [(vec![4, -5, 7], vec![23, 4]), (vec![4], vec![13, 4])] {
+        let c_in: Vec<ParmCiphertext> = m_in.iter().map(|m| common::TEST_PU.encrypt(*m, 5).expect("ParmesanUserovo::encrypt failed.")).collect();
+        let m_he: Vec<i64> = nn.eval(&common::TEST_PC, &c_in).iter().map(|co| common::TEST_PU.decrypt(co).expect("ParmesanUserovo::decrypt failed.")).collect();
+
+        println!("  m_in = {:?}: nn_eval = {:?} (exp. {:?})", m_in, m_he, exp);
+
+        assert_eq!(nn.eval(&common::TEST_PC, &m_in), exp);
+        assert_eq!(m_he, exp);
+    }
+}
+
+#[test]
+/// NN validation of malformed networks.
+fn t_nn_validate() {
+    // wrong number of weights in the 2nd layer
+    let yaml_str = "n_inputs: 2\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: LIN, w: [1, 2], b: 0}\n  - {t: ACT, w: [3, 4], b: 1}\n- layer: rescale\n  pos: 1\n- layer: dense\n  perceptrons:\n  - {t: MAX, w: [1, 2, 3], b: 0}\n";
+    assert!(NeuralNetwork::from_yaml_str(yaml_str).is_err());
+    assert!(NeuralNetwork::from_yaml_str(&yaml_str.replace("[1, 2, 3]", "[1, 2]")).is_ok());
+    // wrong number of weights in the 1st layer
+    let json_str = r#"{"n_inputs": 3, "layers": [{"layer": "dense", "perceptrons": [{"t": "LIN", "w": [1, 2], "b": 0}]}]}"#;
+    assert!(NeuralNetwork::from_json_str(json_str).is_err());
+    // empty layer, no layers
+    assert!(NeuralNetwork::from_json_str(r#"{"n_inputs": 2, "layers": [{"layer": "dense", "perceptrons": []}]}"#).is_err());
+    assert!(NeuralNetwork::from_json_str(r#"{"n_inputs": 2, "layers": []}"#).is_err());
+    // unknown perceptron / layer type
+    assert!(NeuralNetwork::from_json_str(r#"{"n_inputs": 1, "layers": [{"layer": "dense", "perceptrons": [{"t": "XOR", "w": [1], "b": 0}]}]}"#).is_err());
+    assert!(NeuralNetwork::from_json_str(r#"{"n_inputs": 1, "layers": [{"layer": "sparse", "perceptrons": []}]}"#).is_err());
+    // convolution: input not divisible into channels, kernel larger than input, wrong number of kernel weights
+    let conv_str = r#"{"n_inputs": 8, "layers": [{"layer": "conv1d", "in_channels": 2, "kernel_size": 3, "stride": 1, "padding": 0, "kernels": [{"t": "LIN", "w": [1, 1, 1, 1, 1, 1], "b": 0}]}]}"#;
+    assert!(NeuralNetwork::from_json_str(conv_str).is_ok());
+    assert!(NeuralNetwork::from_json_str(&conv_str.replace("\"n_inputs\": 8", "\"n_inputs\": 7")).is_err());
+    assert!(NeuralNetwork::from_json_str(&conv_str.replace("\"kernel_size\": 3", "\"kernel_size\": 5")).is_err());
+    assert!(NeuralNetwork::from_json_str(&conv_str.replace("[1, 1, 1, 1, 1, 1]", "[1, 1, 1]")).is_err());
+    // pooling: average over an area that is not a power of two, zero stride
+    let pool_str = r#"{"n_inputs": 9, "layers": [{"layer": "pool2d", "op": "avg", "in_channels": 1, "in_width": 3, "size": 2, "stride": 1}]}"#;
+    assert!(NeuralNetwork::from_json_str(pool_str).is_ok());
+    assert!(NeuralNetwork::from_json_str(&pool_str.replace("\"size\": 2", "\"size\": 3")).is_err());
+    assert!(NeuralNetwork::from_json_str(&pool_str.replace("\"stride\": 1", "\"stride\": 0")).is_err());
+    // unknown file format
+    assert!(demo_nn().save_to_file("nn.txt").is_err());
+    assert!(NeuralNetwork::from_file("nonexistent-nn.json").is_err());
 }
 
 
@@ -40,10 +347,10 @@ fn t_nn_eval_some_triv() {
 //  Test Implementations
 
 /// Implementation for three variants of vector to be evaluated.
-fn t_impl_nn_eval_with_mode(mode: EncrVsTriv) {
+fn t_impl_nn_eval_with_mode(mode: EncrVsTriv, gen_nn: fn() -> NeuralNetwork) {
     for _ in 0..common::TESTS_REPEAT_NNE {
         // generate random NN
-        let nn = t_gen_nn();
+        let nn = gen_nn();
 
         // generate random inputs
         let mut m_in_vec = vec![];
@@ -115,16 +422,84 @@ fn t_gen_nn() -> NeuralNetwork {
                 t: rand::random(),
                 w: gen_w(in_len),
                 b: rng.gen_range(-common::TESTS_NNE_B_ABS_MAX..=common::TESTS_NNE_B_ABS_MAX),
+                a: rand::random(),
             });
         }
 
-        layers.push(layer);
+        layers.push(Layer::Dense {perceptrons: layer});
+
+        // occasionally rescale
+        if rng.gen_bool(0.3) {
+            layers.push(Layer::Rescale {pos: rng.gen_range(1..=2)});
+        }
 
         // number of perceptrons is the number of inputs to the next layer
         in_len = layer_len;
     }
 
-    NeuralNetwork {layers, n_inputs}
+    NeuralNetwork {layers, n_inputs, argmax: false}
+}
+
+fn t_gen_cnn() -> NeuralNetwork {
+    let mut rng = rand::thread_rng();
+
+    // input: c channels of h×w (1D if h = 1)
+    let c = rng.gen_range(1..=2);
+    let h = rng.gen_range(1..=3);
+    let w = rng.gen_range(2..=4);
+    let n_inputs = c * h * w;
+    let mut layers = vec![];
+
+    // convolution (kernel fits the padded input)
+    let padding = rng.gen_range(0..=1);
+    let kernel_size = rng.gen_range(1..=(h + 2 * padding).min(w + 2 * padding).min(3));
+    let stride = rng.gen_range(1..=2);
+    let n_kernels = rng.gen_range(1..=2);
+    let k_area = if h == 1 {kernel_size} else {kernel_size * kernel_size};
+    let kernels = (0..n_kernels).map(|_| Perceptron {
+        t: rand::random(),
+        w: gen_w(c * k_area),
+        b: rng.gen_range(-common::TESTS_NNE_B_ABS_MAX..=common::TESTS_NNE_B_ABS_MAX),
+        a: rand::random(),
+    }).collect();
+    let conv = if h == 1 {
+        Layer::Conv1d {in_channels: c, kernel_size, stride, padding, kernels}
+    } else {
+        Layer::Conv2d {in_channels: c, in_width: w, kernel_size, stride, padding, kernels}
+    };
+    let conv_len = conv.out_len(n_inputs).expect("Layer::out_len failed.");
+    let ho = if h == 1 {1} else {(h + 2 * padding - kernel_size) / stride + 1};
+    let wo = conv_len / (n_kernels * ho);
+    layers.push(conv);
+
+    // pooling of size 2 (if fits)
+    let op = [PoolOp::Sum, PoolOp::Avg, PoolOp::Max][rng.gen_range(0..3)];
+    let pool = if h == 1 && wo >= 2 {
+        Some(Layer::Pool1d {op, in_channels: n_kernels, size: 2, stride: rng.gen_range(1..=2)})
+    } else if ho >= 2 && wo >= 2 {
+        Some(Layer::Pool2d {op, in_channels: n_kernels, in_width: wo, size: 2, stride: rng.gen_range(1..=2)})
+    } else {
+        None
+    };
+    let mut in_len = conv_len;
+    if let Some(pool) = pool {
+        in_len = pool.out_len(in_len).expect("Layer::out_len failed.");
+        layers.push(pool);
+    }
+
+    // flatten & dense
+    layers.push(Layer::Flatten);
+    let layer_len = rng.gen_range(1..common::TESTS_NNE_LAYER_SIZE);
+    layers.push(Layer::Dense {perceptrons: (0..layer_len).map(|_| Perceptron {
+        t: rand::random(),
+        w: gen_w(in_len),
+        b: rng.gen_range(-common::TESTS_NNE_B_ABS_MAX..=common::TESTS_NNE_B_ABS_MAX),
+        a: rand::random(),
+    }).collect()});
+
+    let nn = NeuralNetwork {layers, n_inputs, argmax: false};
+    nn.validate().expect("NeuralNetwork::validate failed.");
+    nn
 }
 
 fn gen_w(wlen: usize) -> Vec<i32> {
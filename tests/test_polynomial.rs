@@ -0,0 +1,194 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::arithmetics::ParmArithmetics;
+use parmesan::ParmFixed;
+use parmesan::polynomial;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Exponentiation of encrypted sub-samples only.
+fn t_pow_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_pow_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Exponentiation of trivial sub-samples only.
+fn t_pow_all_triv() {
+    println!("All-Triv ...");
+    t_impl_pow_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Exponentiation of mixed sub-samples.
+fn t_pow_some_triv() {
+    println!("Mixed ...");
+    t_impl_pow_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Polynomial evaluation of encrypted sub-samples only.
+fn t_poly_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_poly_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Polynomial evaluation of trivial sub-samples only.
+fn t_poly_all_triv() {
+    println!("All-Triv ...");
+    t_impl_poly_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Polynomial evaluation of mixed sub-samples.
+fn t_poly_some_triv() {
+    println!("Mixed ...");
+    t_impl_poly_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Exponentiation & polynomial evaluation of long ciphertexts (of short values), s.t. parallel products use Karatsuba-long operands.
+fn t_pow_poly_long() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_POLY {
+        // short value, padded with encrypted zeros
+        let mut m_vec = gen_rand_vec(common::TESTS_BITLEN_POLY);
+        m_vec.resize(common::TESTS_LEN_POLY_LONG, 0);
+        let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+        let c = encrypt_with_mode(&m_vec, EncrVsTriv::ENCR);
+
+        // X·X^2 in parallel with X^4
+        let c_he = ParmArithmetics::pow(&common::TEST_PC, &c, 7);
+        let c_he = ParmArithmetics::trim(&common::TEST_PC, &c_he, 7 * common::TESTS_BITLEN_POLY);
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+        let m_pl = ParmArithmetics::pow(&common::TEST_PC, &m, 7);
+
+        println!("  m = {} ({}-word), pow = {} (exp. {})", m, common::TESTS_LEN_POLY_LONG, m_he, m_pl);
+        assert_eq!(m_he, m_pl);
+
+        // (c_2 + c_3·X)·X^2 in parallel with c_4 (and X^4)
+        let coeffs: Vec<i64> = (0..=4).map(|_| rng.gen_range(-(1 << common::TESTS_BITLEN_POLY_COEF)..=(1 << common::TESTS_BITLEN_POLY_COEF))).collect();
+        let c_he = ParmArithmetics::poly_eval(&common::TEST_PC, &c, &coeffs);
+        let c_he = ParmArithmetics::trim(&common::TEST_PC, &c_he, 4 * common::TESTS_BITLEN_POLY + common::TESTS_BITLEN_POLY_COEF + 1);
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+        let m_pl = ParmArithmetics::poly_eval(&common::TEST_PC, &m, &coeffs);
+
+        println!("  coeffs = {:?}, poly = {} (exp. {})", coeffs, m_he, m_pl);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+#[test]
+/// Exponentiation & polynomial evaluation whose squarings exceed the length limit.
+fn t_pow_poly_too_long() {
+    let m_vec = gen_rand_vec(2 * common::TESTS_LEN_POLY_LONG);
+    let c = encrypt_with_mode(&m_vec, EncrVsTriv::TRIV);
+    let cx = ParmFixed::new(c.clone(), common::TESTS_FRAC_FIXED);
+
+    // X^2 is too long to be squared
+    assert!(polynomial::pow_impl(&common::TEST_PC, &c, 4).is_err());
+    assert!(polynomial::poly_eval_impl(&common::TEST_PC, &c, &[1, 0, 0, 0, 1]).is_err());
+    assert!(ParmFixed::poly_eval(&common::TEST_PC, &cx, &[1.0, 0.0, 0.0, 0.0, 1.0]).is_err());
+}
+
+#[test]
+/// Polynomial evaluation with fractional coefficients (sigmoid approximation).
+fn t_poly_fixed() {
+    let mut rng = rand::thread_rng();
+
+    // sigmoid ≈ 1/2 + x/4 - x^3/48
+    let coeffs = [0.5, 0.25, 0.0, -1.0 / 48.0];
+    let frac = common::TESTS_FRAC_FIXED + 4;
+
+    for _ in 0..common::TESTS_REPEAT_POLY {
+        let x: f64 = rng.gen_range(-2.0..2.0);
+        let cx = common::TEST_PU.encrypt_fixed(x, frac, frac + 3).expect("ParmesanUserovo::encrypt_fixed failed.");
+        let xq = common::TEST_PU.decrypt_fixed(&cx).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        let c_he = ParmFixed::poly_eval(&common::TEST_PC, &cx, &coeffs).expect("ParmFixed::poly_eval failed.");
+        let m_he = common::TEST_PU.decrypt_fixed(&c_he).expect("ParmesanUserovo::decrypt_fixed failed.");
+
+        // plain eval (of quantized coefficients)
+        let m_pl: f64 = coeffs.iter().enumerate()
+            .map(|(i, c)| ParmFixed::dequantize(ParmFixed::quantize(*c, frac).expect("ParmFixed::quantize failed."), frac) * xq.powi(i as i32))
+            .sum();
+
+        println!("  x = {}\n  poly = {} (exp. {})", xq, m_he, m_pl);
+
+        // n.b., each product is rounded
+        assert_eq!(c_he.frac, frac);
+        assert!((m_he - m_pl).abs() <= 4.0 / (1 << frac) as f64);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Test Implementations
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_pow_with_mode(mode: EncrVsTriv) {
+    for _ in 0..common::TESTS_REPEAT_POW {
+        for e in 0..=common::TESTS_POW_MAX_EXP {
+            // generate random vector, convert to integer & encrypt
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_POW);
+            let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+            let c = encrypt_with_mode(&m_vec, mode);
+
+            println!("  m = {} ({}-bit: {:?}), e = {}", m, common::TESTS_BITLEN_POW, m_vec, e);
+
+            // homomorphic eval -> decrypt
+            let c_he = ParmArithmetics::pow(&common::TEST_PC, &c, e);
+            let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+            // plain eval
+            let m_pl = ParmArithmetics::pow(&common::TEST_PC, &m, e);
+
+            println!("  pow = {} (exp. {})", m_he, m_pl);
+
+            // compare results
+            assert_eq!(m_he, m_pl);
+        }
+    }
+}
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_poly_with_mode(mode: EncrVsTriv) {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_POLY {
+        for deg in 0..=common::TESTS_POLY_MAX_DEG {
+            // generate random coefficients (incl. zeros) & vector
+            let coeffs: Vec<i64> = (0..=deg).map(|_| if rng.gen_bool(0.2) {0} else {rng.gen_range(-(1 << common::TESTS_BITLEN_POLY_COEF)..=(1 << common::TESTS_BITLEN_POLY_COEF))}).collect();
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_POLY);
+            let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+            let c = encrypt_with_mode(&m_vec, mode);
+
+            println!("  m = {} ({}-bit: {:?}), coeffs = {:?}", m, common::TESTS_BITLEN_POLY, m_vec, coeffs);
+
+            // homomorphic eval -> decrypt
+            let c_he = ParmArithmetics::poly_eval(&common::TEST_PC, &c, &coeffs);
+            let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+            // plain eval
+            let m_pl = ParmArithmetics::poly_eval(&common::TEST_PC, &m, &coeffs);
+
+            println!("  poly = {} (exp. {})", m_he, m_pl);
+
+            // compare results
+            assert_eq!(m_he, m_pl);
+        }
+    }
+}
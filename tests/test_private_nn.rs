@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::*;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Private NN Evaluation: encrypted weights & inputs vs. plain evaluation.
+fn t_private_nn_eval() {
+    for _ in 0..common::TESTS_REPEAT_NNE {
+        let nn = t_gen_nn();
+
+        // model owner encrypts weights & biases
+        let pnn = common::TEST_PU.encrypt_nn(&nn, common::TESTS_NNE_PARAM_WORDS).expect("ParmesanUserovo::encrypt_nn failed.");
+        pnn.validate().expect("PrivateNetwork::validate failed.");
+
+        // generate & encrypt random inputs
+        let mut m_in = vec![];
+        let mut c_in = vec![];
+        for _ in 0..nn.n_inputs {
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_NNE);
+            m_in.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+            c_in.push(encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV));
+        }
+
+        // homomorphic eval
+        let c_he = pnn.eval(&common::TEST_PC, &c_in, common::TESTS_BITLEN_NNE).expect("PrivateNetwork::eval failed.");
+        let m_he: Vec<i64> = c_he.iter().map(|co| common::TEST_PU.decrypt(co).expect("ParmesanUserovo::decrypt failed.")).collect();
+
+        // plain eval: of the original network & of the private network over i64
+        let m_pl = nn.eval(&common::TEST_PC, &m_in);
+        let pnn_pl = PrivateNetwork::<i64>::from_nn(&nn, common::TESTS_NNE_PARAM_WORDS, Ok).expect("PrivateNetwork::from_nn failed.");
+        assert_eq!(pnn_pl.eval(&common::TEST_PC, &m_in, common::TESTS_BITLEN_NNE).expect("PrivateNetwork::eval failed."), m_pl);
+
+        println!("  private nn_eval = {:?}\n  (exp. {:?})", m_he, m_pl);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+#[test]
+/// Private NN: unsupported layers, parameters out of range, weights in public layers.
+fn t_private_nn_validate() {
+    let nn = NeuralNetwork::from_yaml_str("n_inputs: 2\nlayers:\n- layer: dense\n  perceptrons:\n  - {t: LIN, w: [3, -2], b: 20}\n").expect("NeuralNetwork::from_yaml_str failed.");
+
+    // bias 20 needs 5 words
+    assert!(common::TEST_PU.encrypt_nn(&nn, 4).is_err());
+    assert!(common::TEST_PU.encrypt_nn(&nn, 5).is_ok());
+    assert!(common::TEST_PU.encrypt_nn(&nn, 0).is_err());
+
+    // convolution not supported
+    let conv_nn = NeuralNetwork::from_json_str(r#"{"n_inputs": 4, "layers": [{"layer": "conv1d", "in_channels": 1, "kernel_size": 2, "stride": 1, "padding": 0, "kernels": [{"t": "LIN", "w": [1, -1], "b": 0}]}]}"#).expect("NeuralNetwork::from_json_str failed.");
+    assert!(PrivateNetwork::<i64>::from_nn(&conv_nn, 5, Ok).is_err());
+
+    // public layer with weights, wrong number of private weights
+    let mut pnn = PrivateNetwork::<i64>::from_nn(&nn, 5, Ok).expect("PrivateNetwork::from_nn failed.");
+    assert!(pnn.validate().is_ok());
+
+    // worst-case outputs do not fit in 62 bits, wrong number of inputs
+    assert_eq!(pnn.eval(&common::TEST_PC, &[3, -1], 10).expect("PrivateNetwork::eval failed."), vec![31]);
+    assert!(pnn.eval(&common::TEST_PC, &[3, -1], 57).is_err());
+    assert!(pnn.eval(&common::TEST_PC, &[3, -1], 100).is_err());
+    assert!(pnn.eval(&common::TEST_PC, &[3], 10).is_err());
+
+    // worst-case sum beyond i128
+    let wide = PrivateNetwork::<i64> {
+        layers: vec![PrivateLayer::Dense {perceptrons: vec![PrivatePerceptron {t: PercType::LIN, w: vec![1; 16], b: 0, a: Activation::Identity}]}],
+        n_inputs: 16,
+        param_bits: 62,
+        argmax: false,
+    };
+    assert!(wide.validate().is_ok());
+    assert!(wide.eval(&common::TEST_PC, &[0; 16], 62).is_err());
+
+    pnn.layers.push(PrivateLayer::Public(nn.layers[0].clone()));
+    assert!(pnn.validate().is_err());
+    pnn.layers.pop();
+    if let PrivateLayer::Dense {perceptrons} = &mut pnn.layers[0] {perceptrons[0].w.push(1);}
+    assert!(pnn.validate().is_err());
+}
+
+
+// -----------------------------------------------------------------------------
+//  Generate Random NN (dense, rescaling & pooling layers)
+
+fn t_gen_nn() -> NeuralNetwork {
+    let mut rng = rand::thread_rng();
+
+    let depth = rng.gen_range(1..=common::TESTS_NNE_DEPTH);
+    let n_inputs: usize = rng.gen_range(1..=common::TESTS_NNE_LAYER_SIZE);
+    let mut in_len = n_inputs;
+    let mut layers = vec![];
+
+    for _ in 0..depth {
+        let layer_len = rng.gen_range(1..common::TESTS_NNE_LAYER_SIZE);
+        layers.push(Layer::Dense {perceptrons: (0..layer_len).map(|_| Perceptron {
+            t: rand::random(),
+            w: (0..in_len).map(|_| rng.gen_range(-7..=7)).collect(),
+            b: rng.gen_range(-common::TESTS_NNE_B_ABS_MAX..=common::TESTS_NNE_B_ABS_MAX),
+            a: rand::random(),
+        }).collect()});
+        in_len = layer_len;
+
+        // occasionally rescale or pool
+        if rng.gen_bool(0.3) {
+            layers.push(Layer::Rescale {pos: rng.gen_range(1..=2)});
+        } else if in_len >= 2 && rng.gen_bool(0.3) {
+            layers.push(Layer::Pool1d {op: PoolOp::Max, in_channels: 1, size: 2, stride: 1});
+            in_len -= 1;
+        }
+    }
+
+    let nn = NeuralNetwork {layers, n_inputs, argmax: false};
+    nn.validate().expect("NeuralNetwork::validate failed.");
+    nn
+}
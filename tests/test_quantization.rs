@@ -0,0 +1,137 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::*;
+
+#[allow(dead_code)]
+mod common;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Quantization of a hand-made network.
+fn t_quant_simple() {
+    let fnn = FloatNetwork {
+        layers: vec![
+            vec![
+                FloatPerceptron {t: PercType::LIN, w: vec![0.5, -0.25], b: 0.125, a: Activation::Relu},
+                FloatPerceptron {t: PercType::ACT, w: vec![-0.375, 0.0625], b: -0.5, a: Activation::HardTanh {frac: 0}},
+            ],
+        ],
+        n_inputs: 2,
+    };
+
+    // |w| ≤ 7 .. 0.5·2^3 = 4 fits, 0.5·2^4 = 8 does not
+    let qnn = QuantizedNetwork::quantize(&fnn, QuantParams {w_bits: 4, frac: 2}).expect("QuantizedNetwork::quantize failed.");
+
+    assert_eq!(qnn.w_exps, vec![3]);
+    assert_eq!(qnn.out_frac, 2);
+    assert_eq!(qnn.nn, NeuralNetwork {
+        layers: vec![
+            Layer::Dense {perceptrons: vec![
+                Perceptron {t: PercType::LIN, w: vec![4, -2], b: 4, a: Activation::Relu},
+                Perceptron {t: PercType::ACT, w: vec![-3, 1], b: -16, a: Activation::HardTanh {frac: 5}},
+            ]},
+            Layer::Rescale {pos: 3},
+        ],
+        n_inputs: 2,
+        argmax: false,
+    });
+
+    // exact for inputs & parameters representable in the fixed-point format
+    let report = qnn.report(&common::TEST_PC, &fnn, &[vec![1.0, -0.5], vec![-0.25, 0.75]], Some(&[0, 1])).expect("QuantizedNetwork::report failed.");
+    println!("  {}", report);
+    assert!(report.max_abs_err <= 0.25);
+    assert!(NeuralNetwork::validate(&qnn.nn).is_ok());
+
+    // wrong bit budget
+    assert!(QuantizedNetwork::quantize(&fnn, QuantParams {w_bits: 1, frac: 2}).is_err());
+    // sign does not scale
+    let mut fnn_sgn = fnn.clone();
+    fnn_sgn.layers[0][1].a = Activation::Sign;
+    assert!(QuantizedNetwork::quantize(&fnn_sgn, QuantParams {w_bits: 4, frac: 2}).is_err());
+}
+
+#[test]
+/// Quantization of random networks: budget, accuracy & encrypted evaluation.
+fn t_quant_random() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_QUANT {
+        let fnn = t_gen_float_nn();
+
+        // random inputs in [-1, 1]
+        let inputs: Vec<Vec<f64>> = (0..common::TESTS_REPEAT_QUANT).map(|_| (0..fnn.n_inputs).map(|_| rng.gen_range(-1.0..=1.0)).collect()).collect();
+
+        // coarse & fine quantization
+        let mut errs = vec![];
+        for qp in [
+            QuantParams {w_bits: common::TESTS_QUANT_W_BITS, frac: common::TESTS_QUANT_FRAC},
+            QuantParams {w_bits: 2 * common::TESTS_QUANT_W_BITS, frac: 2 * common::TESTS_QUANT_FRAC},
+        ] {
+            let qnn = QuantizedNetwork::quantize(&fnn, qp).expect("QuantizedNetwork::quantize failed.");
+
+            // weights within budget
+            for layer in qnn.nn.layers.iter() {
+                if let Layer::Dense {perceptrons} = layer {
+                    for perc in perceptrons {
+                        assert!(perc.w.iter().all(|wi| wi.abs() < (1 << (qp.w_bits - 1))));
+                    }
+                }
+            }
+
+            let report = qnn.report(&common::TEST_PC, &fnn, &inputs, None).expect("QuantizedNetwork::report failed.");
+            println!("  {:?}: {}", qp, report);
+            errs.push(report.max_abs_err);
+        }
+        // finer quantization is (up to some noise) more accurate
+        assert!(errs[1] <= errs[0] + 1e-3);
+        assert!(errs[1] < 0.05);
+
+        // encrypted evaluation matches plain evaluation of quantized network
+        let qnn = QuantizedNetwork::quantize(&fnn, QuantParams {w_bits: common::TESTS_QUANT_W_BITS, frac: common::TESTS_QUANT_FRAC}).expect("QuantizedNetwork::quantize failed.");
+        let m_in = qnn.quantize_inputs(&inputs[0]).expect("QuantizedNetwork::quantize_inputs failed.");
+        let c_in: Vec<ParmCiphertext> = m_in.iter().map(|mi| common::TEST_PU.encrypt(*mi, common::TESTS_QUANT_FRAC + 2).expect("ParmesanUserovo::encrypt failed.")).collect();
+
+        let c_he = qnn.nn.eval(&common::TEST_PC, &c_in);
+        let m_he: Vec<i64> = c_he.iter().map(|ci| common::TEST_PU.decrypt(ci).expect("ParmesanUserovo::decrypt failed.")).collect();
+        let m_pl = qnn.nn.eval(&common::TEST_PC, &m_in);
+
+        println!("  nn_eval = {:?}\n  (exp. {:?})", m_he, m_pl);
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Generate Random Floating-Point NN
+
+fn t_gen_float_nn() -> FloatNetwork {
+    let mut rng = rand::thread_rng();
+
+    let depth = rng.gen_range(1..=common::TESTS_NNE_DEPTH);
+    let n_inputs: usize = rng.gen_range(1..=common::TESTS_NNE_LAYER_SIZE);
+    let mut in_len = n_inputs;
+    let mut layers = vec![];
+
+    for _ in 0..depth {
+        let layer_len = rng.gen_range(1..common::TESTS_NNE_LAYER_SIZE);
+        layers.push((0..layer_len).map(|_| FloatPerceptron {
+            t: rand::random(),
+            w: (0..in_len).map(|_| rng.gen_range(-1.0..=1.0)).collect(),
+            b: rng.gen_range(-0.5..=0.5),
+            // sign cannot be quantized
+            a: match rand::random() {
+                Activation::Sign => Activation::Relu,
+                a => a,
+            },
+        }).collect());
+        in_len = layer_len;
+    }
+
+    FloatNetwork {layers, n_inputs}
+}
@@ -6,6 +6,7 @@ use rand::Rng;
 use parmesan::userovo::encryption;
 use parmesan::arithmetics::ParmArithmetics;
 use parmesan::ciphertexts::{ParmCiphertext, ParmCiphertextImpl};
+use parmesan::RoundingMode;
 
 #[allow(dead_code)]
 mod common;
@@ -57,6 +58,53 @@ fn t_round_empty() {
 
 //TODO identify other special cases?
 
+// Rounding Modes
+
+#[test]
+/// Rounding with all modes, mixed sub-samples (incl. positions beyond the length).
+fn t_round_modes() {
+    for mode in [EncrVsTriv::ENCR, EncrVsTriv::TRIV, EncrVsTriv::ENCRTRIV] {
+        for rm in ROUNDING_MODES {
+            println!("{:?} ...", rm);
+            t_impl_round_with_mode_and_rounding(mode, rm, false);
+        }
+    }
+}
+
+#[test]
+/// Noisy rounding with all modes.
+fn t_round_noisy() {
+    for rm in ROUNDING_MODES {
+        println!("{:?} ...", rm);
+        t_impl_round_with_mode_and_rounding(EncrVsTriv::ENCRTRIV, rm, true);
+    }
+}
+
+#[test]
+/// Plain rounding modes against floating-point reference.
+fn t_round_modes_plain() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_ROUND {
+        let m: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_ROUND)..=(1 << common::TESTS_BITLEN_ROUND));
+        for pos in 1..=common::TESTS_POS_ROUND {
+            let q = m as f64 / (1 << pos) as f64;
+            for rm in ROUNDING_MODES {
+                let q_ref = match rm {
+                    RoundingMode::HalfUp        => (q + 0.5).floor(),
+                    RoundingMode::HalfAway      => q.round(),
+                    RoundingMode::HalfEven      => q.round_ties_even(),
+                    RoundingMode::Floor         => q.floor(),
+                    RoundingMode::Ceil          => q.ceil(),
+                    RoundingMode::TowardZero    => q.trunc(),
+                };
+                assert_eq!(ParmArithmetics::round_at_mode(&common::TEST_PC, &m, pos, rm), (q_ref as i64) << pos);
+            }
+            assert_eq!(ParmArithmetics::round_at_mode(&common::TEST_PC, &m, pos, RoundingMode::HalfUp), ParmArithmetics::round_at(&common::TEST_PC, &m, pos));
+        }
+    }
+}
+
 
 // -----------------------------------------------------------------------------
 //  Test Implementations
@@ -90,3 +138,50 @@ fn t_impl_round_with_mode(mode: EncrVsTriv) {
         assert_eq!(m_he, m_pl);
     }
 }
+
+/// Implementation for given rounding mode (and noisiness)
+fn t_impl_round_with_mode_and_rounding(
+    mode: EncrVsTriv,
+    rm: RoundingMode,
+    noisy: bool,
+) {
+    // for random position generation
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..common::TESTS_REPEAT_ROUND {
+        // generate random vector(s)
+        let m1_vec = gen_rand_vec(common::TESTS_BITLEN_ROUND);
+        // convert to integer(s)
+        let m1 = encryption::convert_from_vec(&m1_vec).expect("convert failed.");
+        // generate random position (incl. beyond the length)
+        let pos: usize = rng.gen_range(0..=common::TESTS_BITLEN_ROUND + 2);
+
+        println!("  m1 = {} ({}-bit: {:?}), pos = {}", m1, common::TESTS_BITLEN_ROUND, m1_vec, pos);
+
+        // encrypt -> homomorphic eval -> decrypt
+        let c1 = encrypt_with_mode(&m1_vec, mode);
+        let c_he = if noisy {
+            ParmArithmetics::round_at_noisy(&common::TEST_PC, &c1, pos, rm)
+        } else {
+            ParmArithmetics::round_at_mode(&common::TEST_PC, &c1, pos, rm)
+        };
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+        // plain eval
+        let m_pl = ParmArithmetics::round_at_mode(&common::TEST_PC, &m1, pos, rm);
+
+        println!("round = {} (exp. {})", m_he, m_pl);
+
+        // compare results
+        assert_eq!(m_he, m_pl);
+    }
+}
+
+static ROUNDING_MODES: [RoundingMode; 6] = [
+    RoundingMode::HalfUp,
+    RoundingMode::HalfAway,
+    RoundingMode::HalfEven,
+    RoundingMode::Floor,
+    RoundingMode::Ceil,
+    RoundingMode::TowardZero,
+];
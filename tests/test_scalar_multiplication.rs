@@ -5,6 +5,7 @@ use rand::Rng;
 
 use parmesan::userovo::encryption::{self, *};
 use parmesan::arithmetics::ParmArithmetics;
+use parmesan::ciphertexts::ParmCiphertext;
 use parmesan::scalar_multiplication::*;
 use parmesan::*;
 
@@ -52,6 +53,86 @@ fn t_scm_pow_2() {
         t_impl_scm_with_mode_and_scalar(mode,-4);
         t_impl_scm_with_mode_and_scalar(mode, 256);
         t_impl_scm_with_mode_and_scalar(mode,-256);
+        t_impl_scm_with_mode_and_scalar(mode, 1 << 40);
+        t_impl_scm_with_mode_and_scalar(mode,-(1 << 40));
+    }
+}
+
+#[test]
+/// Expected bootstrap depth of scalar multiplication.
+fn t_scm_pbs_depth() {
+    // for random scalar generation
+    let mut rng = rand::thread_rng();
+
+    // no bootstrap for 0 & ±2^t, a single addition for ±3·2^t
+    for (k, d) in [(0, 0), (1, 0), (-1, 0), (256, 0), (-(1 << 40), 0), (3, 2), (-3 << 7, 2)] {
+        assert_eq!(scm_pbs_depth(k, ScmObjective::Throughput, None).expect("scm_pbs_depth failed."), d);
+    }
+
+    for _ in 0..common::TESTS_REPEAT_RECODING {
+        let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR)..=(1 << common::TESTS_BITLEN_SCALAR));
+        let d_thr = scm_pbs_depth(k, ScmObjective::Throughput, None).expect("scm_pbs_depth failed.");
+        let d_lat = scm_pbs_depth(k, ScmObjective::Latency, None).expect("scm_pbs_depth failed.");
+
+        println!("  k = {}: depth {} (throughput), {} (latency)", k, d_thr, d_lat);
+
+        // the whole scalar multiplication is an ASC for k
+        assert!(d_lat >= 2 * asc_search::min_depth(k.unsigned_abs()));
+        assert!(d_lat <= d_thr);
+    }
+}
+
+// Dot Product
+
+#[test]
+/// Dot product with constant weights, encrypted sub-samples only.
+fn t_dot_const_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_dot_const_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Dot product with constant weights, trivial sub-samples only.
+fn t_dot_const_all_triv() {
+    println!("All-Triv ...");
+    t_impl_dot_const_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Dot product with constant weights, mixed sub-samples.
+fn t_dot_const_some_triv() {
+    println!("Mixed ...");
+    t_impl_dot_const_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Dot product with specific weights
+fn t_dot_const_special() {
+    for mode in [EncrVsTriv::ENCR, EncrVsTriv::TRIV, EncrVsTriv::ENCRTRIV] {
+        t_impl_dot_const_with_mode_and_weights(mode, &[], 0);
+        t_impl_dot_const_with_mode_and_weights(mode, &[], -5);
+        t_impl_dot_const_with_mode_and_weights(mode, &[0, 0, 0], 7);
+        t_impl_dot_const_with_mode_and_weights(mode, &[1, -1, 1], 0);
+        t_impl_dot_const_with_mode_and_weights(mode, &[-256, 3, 0, 3], -1);
+    }
+}
+
+// Multi-Scalar Multiplication
+
+#[test]
+/// Multi-scalar multiplication, incl. zero, repeated, opposite & shifted scalars.
+fn t_multi_scm() {
+    // for random scalar generation
+    let mut rng = rand::thread_rng();
+
+    for mode in [EncrVsTriv::ENCR, EncrVsTriv::TRIV, EncrVsTriv::ENCRTRIV] {
+        for _ in 0..common::TESTS_REPEAT_DOT {
+            let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_DOT_W)..=(1 << common::TESTS_BITLEN_DOT_W));
+            let mut ks: Vec<i64> = (0..common::TESTS_N_DOT_MAX).map(|_| rng.gen_range(-(1 << common::TESTS_BITLEN_DOT_W)..=(1 << common::TESTS_BITLEN_DOT_W))).collect();
+            ks.extend([0, k, k, -k, k << 3, -(k << 5), 1, -2]);
+            t_impl_multi_scm_with_mode_and_scalars(mode, &ks);
+        }
+        t_impl_multi_scm_with_mode_and_scalars(mode, &[]);
     }
 }
 
@@ -71,6 +152,162 @@ fn t_asc() {
     }
 }
 
+#[test]
+/// Depth-optimal Addition-Subtraction Chains: correctness, depth & dependency levels.
+fn t_asc_depth() {
+    println!("ASC depth ...");
+
+    assert_eq!(ASC_12_DEPTH.len(), 2048);
+
+    for (n, asc) in ASC_12_DEPTH.iter() {
+        assert_eq!(*n as i64, asc.value(&common::TEST_PC));
+        assert_eq!(asc.depth(), asc_search::min_depth(*n as u64));
+        assert!(asc.len() <= ASC_12[n].len());
+
+        // every step appears in exactly one level, all its operands in previous levels
+        let lvls = asc.levels();
+        assert_eq!(lvls.len(), asc.depth());
+        assert_eq!(lvls.iter().map(|l| l.len()).sum::<usize>(), asc.len());
+        let depths = asc.depths();
+        for (d, lvl) in lvls.iter().enumerate() {
+            for si in lvl {
+                assert!(depths[asc[*si].l_idx] <= d);
+                assert!(depths[asc[*si].r_idx] <= d);
+            }
+        }
+    }
+}
+
+#[test]
+/// Scalar multiplication with latency objective (depth-optimal ASC's).
+fn t_scm_latency() {
+    println!("Latency ...");
+
+    let mut rng = rand::thread_rng();
+
+    for mode in [EncrVsTriv::ENCR, EncrVsTriv::TRIV, EncrVsTriv::ENCRTRIV] {
+        for _ in 0..common::TESTS_REPEAT_SCM {
+            let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR)..=(1 << common::TESTS_BITLEN_SCALAR));
+            let m_vec = gen_rand_vec(common::TESTS_BITLEN_SCM);
+            let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+
+            println!("  m = {} ({}-bit: {:?})\n  k = {}", m, common::TESTS_BITLEN_SCM, m_vec, k);
+
+            // encrypt -> homomorphic eval -> decrypt
+            let c = encrypt_with_mode(&m_vec, mode);
+            let c_he = scalar_mul_impl(&common::TEST_PC, k, &c, ScmObjective::Latency, None).expect("scalar_mul_impl failed.");
+            let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+            // the same through public API
+            let c_api = ParmArithmetics::scalar_mul_latency(&common::TEST_PC, k, &c);
+            let m_api = common::TEST_PU.decrypt(&c_api).expect("ParmesanUserovo::decrypt failed.");
+
+            println!("  scm = {} (exp. {})", m_he, k * m);
+
+            assert_eq!(m_he, k * m);
+            assert_eq!(m_api, k * m);
+            assert_eq!(ParmArithmetics::scalar_mul_latency(&common::TEST_PC, k, &m), k * m);
+        }
+    }
+}
+
+#[test]
+/// Addition-Subtraction Chains search: correctness, optimality bounds & YAML round-trip.
+fn t_asc_search() {
+    println!("ASC search ...");
+
+    let bitlen = common::TESTS_BITLEN_ASC_SEARCH;
+
+    for objective in [asc_search::AscObjective::Length, asc_search::AscObjective::Depth] {
+        let asc_map = asc_search::search_ascs(bitlen, objective).expect("search_ascs failed.");
+
+        // all odd values below 2^bitlen
+        assert_eq!(asc_map.len(), 1 << (bitlen - 1));
+
+        for (n, asc) in asc_map.iter() {
+            // correct value
+            assert_eq!(*n as i64, asc.value(&common::TEST_PC));
+            // not longer than the NAF-based chain
+            let naf_w = naf::naf_vec(*n as u64).iter().filter(|ni| **ni != 0).count();
+            assert!(asc.len() < naf_w);
+            // depth-optimal
+            if objective == asc_search::AscObjective::Depth {
+                assert_eq!(asc.depth(), asc_search::min_depth(*n as u64));
+            }
+        }
+
+        // YAML round-trip
+        let yaml_str = asc_search::ascs_to_yaml(&asc_map).expect("ascs_to_yaml failed.");
+        let asc_map_loaded = Asc::map_from_yaml_str(bitlen, &yaml_str).expect("Asc::map_from_yaml_str failed.");
+        assert_eq!(asc_map, asc_map_loaded);
+    }
+}
+
+#[test]
+/// Scalar recodings: digits reconstruct k, windows reconstruct k & have ASC's.
+fn t_recodings() {
+    println!("Recodings ...");
+
+    // for random scalar generation
+    let mut rng = rand::thread_rng();
+
+    for rc in recoding::recoding_candidates() {
+        println!("  {}", rc.name());
+
+        for _ in 0..common::TESTS_REPEAT_RECODING {
+            // generate random scalar
+            let k: u64 = rng.gen_range(0..=(1 << common::TESTS_BITLEN_KOY_TSU));
+
+            // digits in {-1,0,1} reconstruct k
+            let k_vec = rc.recode(k);
+            assert!(k_vec.iter().all(|ki| ki.abs() <= 1));
+            assert_eq!(convert_from_vec(&k_vec).expect("convert failed."), k as i64);
+
+            // windows reconstruct k
+            let ws = rc.windows(k);
+            let k_ws: i128 = ws.iter().map(|(wi, shi)| (*wi as i128) << shi).sum();
+            assert_eq!(k_ws, k as i128);
+            // windows are odd & have ASC's
+            for (wi, _shi) in ws {
+                assert_eq!(wi.abs() % 2, 1);
+                assert!(ASC_12.contains_key(&(wi.unsigned_abs() as usize)));
+            }
+        }
+    }
+
+    // standard NAF is non-adjacent
+    for k in 0..(1u64 << common::TESTS_BITLEN_SCM) {
+        let k_vec = naf::naf_vec(k);
+        assert!(k_vec.windows(2).all(|kk| kk[0] == 0 || kk[1] == 0));
+    }
+}
+
+#[test]
+/// Scalar multiplication with each recoding.
+fn t_scm_recodings() {
+    println!("Recodings ...");
+
+    let mut rng = rand::thread_rng();
+
+    for rc in recoding::recoding_candidates() {
+        println!("  {}", rc.name());
+
+        let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR)..=(1 << common::TESTS_BITLEN_SCALAR));
+        let m_vec = gen_rand_vec(common::TESTS_BITLEN_SCM);
+        let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+
+        println!("  m = {} ({}-bit: {:?})\n  k = {}", m, common::TESTS_BITLEN_SCM, m_vec, k);
+
+        // encrypt -> homomorphic eval -> decrypt
+        let c = encrypt_with_mode(&m_vec, EncrVsTriv::ENCRTRIV);
+        let c_he = scalar_mul_impl(&common::TEST_PC, k, &c, ScmObjective::Throughput, Some(rc.as_ref())).expect("scalar_mul_impl failed.");
+        let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+        println!("  scm = {} (exp. {})", m_he, k * m);
+
+        assert_eq!(m_he, k * m);
+    }
+}
+
 #[test]
 /// Koyama-Tsuruoka representation
 fn t_koy_tsu() {
@@ -81,19 +318,19 @@ fn t_koy_tsu() {
 
     for _ in 0..common::TESTS_REPEAT_KOY_TSU {
         // generate random scalar
-        let k: i32 = rng.gen_range(0..=(1 << common::TESTS_BITLEN_KOY_TSU));
+        let k: i64 = rng.gen_range(0..=(1 << common::TESTS_BITLEN_KOY_TSU));
 
         println!("  k = {}", k);
 
         // calc Koyama-Tsuruoka representation
-        let kt_vec = naf::koyama_tsuruoka_vec(k.abs() as u32);
+        let kt_vec = naf::koyama_tsuruoka_vec(k.unsigned_abs());
         // eval it
         let k_val = convert_from_vec(&kt_vec).expect("convert failed.");
 
         println!("  kt_vec = {:?} ~ {}", kt_vec, k_val);
 
         // compare results
-        assert_eq!(k.abs() as i64, k_val);
+        assert_eq!(k.abs(), k_val);
     }
 }
 
@@ -108,16 +345,19 @@ fn t_impl_scm_with_mode(mode: EncrVsTriv) {
 
     for _ in 0..common::TESTS_REPEAT_SCM {
         // generate random scalar
-        let k: i32 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR)..=(1 << common::TESTS_BITLEN_SCALAR));
+        let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR)..=(1 << common::TESTS_BITLEN_SCALAR));
         // run test with mode & scalar
         t_impl_scm_with_mode_and_scalar(mode, k);
+        // generate random 64-bit scalar (still fits decryption)
+        let k: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_SCALAR_64)..=(1 << common::TESTS_BITLEN_SCALAR_64));
+        t_impl_scm_with_mode_and_scalar(mode, k);
     }
 }
 
 /// Implementation for fixed mode & scalar
 fn t_impl_scm_with_mode_and_scalar(
     mode: EncrVsTriv,
-    k: i32,
+    k: i64,
 ) {
     // generate random vector(s)
     let m1_vec = gen_rand_vec(common::TESTS_BITLEN_SCM);
@@ -139,3 +379,77 @@ fn t_impl_scm_with_mode_and_scalar(
     // compare results
     assert_eq!(m_he, m_pl);
 }
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_dot_const_with_mode(mode: EncrVsTriv) {
+    // for random weights generation
+    let mut rng = rand::thread_rng();
+
+    for n in 1..=common::TESTS_N_DOT_MAX {
+        for _ in 0..common::TESTS_REPEAT_DOT {
+            // generate random weights & bias
+            let ws: Vec<i64> = (0..n).map(|_| rng.gen_range(-(1 << common::TESTS_BITLEN_DOT_W)..=(1 << common::TESTS_BITLEN_DOT_W))).collect();
+            let b: i64 = rng.gen_range(-(1 << common::TESTS_BITLEN_DOT_W)..=(1 << common::TESTS_BITLEN_DOT_W));
+            // run test with mode & weights
+            t_impl_dot_const_with_mode_and_weights(mode, &ws, b);
+        }
+    }
+}
+
+/// Implementation for fixed mode & weights
+fn t_impl_dot_const_with_mode_and_weights(
+    mode: EncrVsTriv,
+    ws: &[i64],
+    b: i64,
+) {
+    let mut ms: Vec<i64> = Vec::new();
+    let mut cs: Vec<ParmCiphertext> = Vec::new();
+
+    for _ in ws {
+        // generate random vector
+        let m_vec = gen_rand_vec(common::TESTS_BITLEN_SCM);
+        // convert to integer & encrypt
+        ms.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+        cs.push(encrypt_with_mode(&m_vec, mode));
+    }
+
+    println!("  ms = {:?}\n  ws = {:?}, b = {}", ms, ws, b);
+
+    // homomorphic eval -> decrypt
+    let c_he = ParmArithmetics::dot_const(&common::TEST_PC, ws, &cs, b);
+    let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+    // plain eval
+    let m_pl = ParmArithmetics::dot_const(&common::TEST_PC, ws, &ms, b);
+
+    println!("  dot = {} (exp. {})", m_he, m_pl);
+
+    // compare results
+    assert_eq!(m_he, m_pl);
+}
+
+/// Implementation for fixed mode & scalars
+fn t_impl_multi_scm_with_mode_and_scalars(
+    mode: EncrVsTriv,
+    ks: &[i64],
+) {
+    // generate random vector
+    let m_vec = gen_rand_vec(common::TESTS_BITLEN_SCM);
+    // convert to integer
+    let m = encryption::convert_from_vec(&m_vec).expect("convert failed.");
+
+    println!("  m = {} ({}-bit: {:?})\n  ks = {:?}", m, common::TESTS_BITLEN_SCM, m_vec, ks);
+
+    // encrypt -> homomorphic eval -> decrypt
+    let c = encrypt_with_mode(&m_vec, mode);
+    let cs_he = ParmArithmetics::multi_scalar_mul(&common::TEST_PC, ks, &c);
+    let ms_he: Vec<i64> = cs_he.iter().map(|c_he| common::TEST_PU.decrypt(c_he).expect("ParmesanUserovo::decrypt failed.")).collect();
+
+    // plain eval
+    let ms_pl = ParmArithmetics::multi_scalar_mul(&common::TEST_PC, ks, &m);
+
+    println!("  multi scm = {:?} (exp. {:?})", ms_he, ms_pl);
+
+    // compare results
+    assert_eq!(ms_he, ms_pl);
+}
@@ -0,0 +1,109 @@
+#[macro_use]
+extern crate lazy_static;
+
+use parmesan::userovo::encryption;
+use parmesan::arithmetics::ParmArithmetics;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Square root of encrypted sub-samples only.
+fn t_isqrt_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_isqrt_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Square root of trivial sub-samples only.
+fn t_isqrt_all_triv() {
+    println!("All-Triv ...");
+    t_impl_isqrt_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Square root of mixed sub-samples.
+fn t_isqrt_some_triv() {
+    println!("Mixed ...");
+    t_impl_isqrt_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Square root around perfect squares.
+fn t_isqrt_squares() {
+    println!("Perfect squares ...");
+
+    let bl = common::TESTS_BITLEN_SQRT;
+    let mut k = 0i64;
+    while (k + 1) * (k + 1) < (1 << bl) {
+        for m in [k * k - 1, k * k, k * k + 1] {
+            t_impl_isqrt_int(m, bl);
+        }
+        k += 1;
+    }
+
+    // plain eval around the largest square of i64
+    let k = 3_037_000_499i64;
+    for (m, exp) in [(k * k - 1, k - 1), (k * k, k), (i64::MAX, k)] {
+        assert_eq!(ParmArithmetics::isqrt(&common::TEST_PC, &m), exp);
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//  Test Implementations
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_isqrt_with_mode(mode: EncrVsTriv) {
+    for _ in 0..common::TESTS_REPEAT_SQRT {
+        for bl in 0..=common::TESTS_BITLEN_SQRT {
+            // generate random vector, evaluate also its negation
+            let m_vec = gen_rand_vec(bl);
+            let m_vec_neg = m_vec.iter().map(|mi| -mi ).collect();
+
+            for mv in [m_vec, m_vec_neg] {
+                // convert to integer
+                let m = encryption::convert_from_vec(&mv).expect("convert failed.");
+
+                println!("  m = {} ({}-bit: {:?})", m, bl, mv);
+
+                // encrypt -> homomorphic eval -> decrypt
+                let c = encrypt_with_mode(&mv, mode);
+                let c_he = ParmArithmetics::isqrt(&common::TEST_PC, &c);
+                let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+                // plain eval
+                let m_pl = ParmArithmetics::isqrt(&common::TEST_PC, &m);
+
+                println!("  isqrt = {} (exp. {})", m_he, m_pl);
+
+                // compare results
+                assert_eq!(m_he, m_pl);
+            }
+        }
+    }
+}
+
+/// Implementation for a given integer.
+fn t_impl_isqrt_int(m: i64, bl: usize) {
+    println!("  m = {} ({}-bit)", m, bl);
+
+    // encrypt -> homomorphic eval -> decrypt
+    let c = common::TEST_PU.encrypt(m, bl).expect("ParmesanUserovo::encrypt failed.");
+    let c_he = ParmArithmetics::isqrt(&common::TEST_PC, &c);
+    let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+    // plain eval
+    let m_pl = ParmArithmetics::isqrt(&common::TEST_PC, &m);
+
+    println!("  isqrt = {} (exp. {})", m_he, m_pl);
+
+    // compare results
+    assert_eq!(m_he, m_pl);
+    assert!(m < 0 || (m_pl * m_pl <= m && m < (m_pl + 1) * (m_pl + 1)));
+}
@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate lazy_static;
+
+use rand::Rng;
+
+use parmesan::userovo::encryption;
+use parmesan::arithmetics::ParmArithmetics;
+use parmesan::ciphertexts::ParmCiphertext;
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+
+// -----------------------------------------------------------------------------
+//  Test Cases
+
+#[test]
+/// Summation of encrypted sub-samples only.
+fn t_sum_non_triv() {
+    println!("Non-Triv ...");
+    t_impl_sum_with_mode(EncrVsTriv::ENCR);
+}
+
+#[test]
+/// Summation of trivial sub-samples only.
+fn t_sum_all_triv() {
+    println!("All-Triv ...");
+    t_impl_sum_with_mode(EncrVsTriv::TRIV);
+}
+
+#[test]
+/// Summation of mixed sub-samples.
+fn t_sum_some_triv() {
+    println!("Mixed ...");
+    t_impl_sum_with_mode(EncrVsTriv::ENCRTRIV);
+}
+
+#[test]
+/// Summation of no operands.
+fn t_sum_empty() {
+    println!("Empty ...");
+    let c_he = ParmArithmetics::sum(&common::TEST_PC, &[] as &[ParmCiphertext]);
+    let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+    assert_eq!(m_he, 0);
+}
+
+
+// -----------------------------------------------------------------------------
+//  Test Implementations
+
+/// Implementation for three variants of vector to be evaluated.
+fn t_impl_sum_with_mode(mode: EncrVsTriv) {
+    let mut rng = rand::thread_rng();
+
+    for n in 1..=common::TESTS_N_SUM_MAX {
+        for _ in 0..common::TESTS_REPEAT_SUM {
+            let mut ms: Vec<i64> = Vec::new();
+            let mut cs: Vec<ParmCiphertext> = Vec::new();
+
+            for _ in 0..n {
+                // generate random vector of random length
+                let bl = rng.gen_range(0..=common::TESTS_BITLEN_SUM);
+                let m_vec = gen_rand_vec(bl);
+                // convert to integer & encrypt
+                ms.push(encryption::convert_from_vec(&m_vec).expect("convert failed."));
+                cs.push(encrypt_with_mode(&m_vec, mode));
+            }
+
+            println!("  ms = {:?}", ms);
+
+            // homomorphic eval -> decrypt
+            let c_he = ParmArithmetics::sum(&common::TEST_PC, &cs);
+            let m_he = common::TEST_PU.decrypt(&c_he).expect("ParmesanUserovo::decrypt failed.");
+
+            // plain eval
+            let m_pl = ParmArithmetics::sum(&common::TEST_PC, &ms);
+
+            println!("  sum = {} (exp. {})", m_he, m_pl);
+
+            // compare results
+            assert_eq!(m_he, m_pl);
+        }
+    }
+}